@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
 /// Create new projects optimized for Claude Code
@@ -7,6 +7,14 @@ use std::path::PathBuf;
 #[command(about = "Create new projects optimized for Claude Code", long_about = None)]
 #[command(version)]
 pub struct Cli {
+    /// Template registry file (TOML or JSON) merged over the built-in templates
+    #[arg(long, global = true, env = "CLAUDEFORGE_REGISTRY")]
+    pub registry: Option<PathBuf>,
+
+    /// Only use cached templates, never access the network
+    #[arg(long, global = true, env = "CLAUDEFORGE_OFFLINE", value_parser = clap::builder::BoolishValueParser::new())]
+    pub offline: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -15,9 +23,12 @@ pub struct Cli {
 pub enum Commands {
     /// Create a new project from a template
     New {
-        /// Language template to use (rust, go)
-        #[arg(value_enum)]
-        language: Language,
+        /// Template to use (see `claudeforge list`)
+        template: String,
+
+        /// Branch, tag or commit SHA of the template to use
+        #[arg(long = "ref", value_name = "REF")]
+        git_ref: Option<String>,
 
         /// Project name
         name: String,
@@ -29,6 +40,14 @@ pub enum Commands {
         /// Skip interactive prompts
         #[arg(short, long)]
         yes: bool,
+
+        /// Set a template variable, may be repeated
+        #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_var)]
+        vars: Vec<(String, String)>,
+
+        /// Read template variables from a TOML, JSON or YAML file
+        #[arg(long, value_name = "FILE")]
+        vars_file: Option<PathBuf>,
     },
 
     /// List available templates
@@ -37,26 +56,131 @@ pub enum Commands {
     /// Update cached templates
     Update,
 
+    /// Manage custom templates
+    Template {
+        #[command(subcommand)]
+        command: TemplateCommands,
+    },
+
+    /// Inspect and prune the template cache
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
+
     /// Show version information
     Version,
 }
 
-#[derive(Debug, Clone, ValueEnum, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
-pub enum Language {
-    #[serde(rename = "rust")]
-    Rust,
-    #[serde(rename = "go")]
-    Go,
-    #[serde(rename = "python")]
-    Python,
+#[derive(Subcommand, Debug)]
+pub enum TemplateCommands {
+    /// Add a custom template
+    Add {
+        /// Template identifier
+        id: String,
+
+        /// Language of the template
+        language: String,
+
+        /// Git repository URL
+        repository: String,
+
+        /// Pin the template to a branch, tag or commit SHA
+        #[arg(long = "ref", value_name = "REF")]
+        git_ref: Option<String>,
+
+        /// Path of the template inside the repository
+        #[arg(long)]
+        subdirectory: Option<String>,
+
+        /// Fetch only the latest commit instead of the full history
+        #[arg(long)]
+        shallow: bool,
+
+        /// Check out only the subdirectory of the repository
+        #[arg(long, requires = "subdirectory")]
+        sparse: bool,
+
+        /// Display name (defaults to the identifier)
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Template description
+        #[arg(short, long)]
+        description: Option<String>,
+
+        /// Files to customize (format: path:placeholder:value_type)
+        #[arg(short, long = "file")]
+        files: Vec<String>,
+    },
+
+    /// Remove a custom template
+    Remove {
+        /// Template identifier
+        id: String,
+    },
+
+    /// Show template details
+    Show {
+        /// Template identifier
+        id: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheCommands {
+    /// List cached templates
+    List,
+
+    /// Remove all cached templates, or only the cached copy of one template
+    Clean {
+        /// Template identifier
+        template: Option<String>,
+
+        /// Also remove every other cached ref and subdirectory of the
+        /// template's repository, including those of other templates
+        #[arg(long, requires = "template")]
+        repository: bool,
+    },
+
+    /// Remove cached templates that were not fetched recently
+    Prune {
+        /// Maximum age, e.g. `30d`, `2w` or `12h`
+        #[arg(long, value_name = "AGE", value_parser = parse_age)]
+        older_than: chrono::Duration,
+    },
+
+    /// Print the cache directory
+    Path,
 }
 
-impl std::fmt::Display for Language {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Language::Rust => write!(f, "rust"),
-            Language::Go => write!(f, "go"),
-            Language::Python => write!(f, "python"),
+/// Parse a `KEY=VALUE` template variable; the value may contain `=`
+pub fn parse_var(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
         }
+        _ => Err(format!("invalid variable '{value}' (expected KEY=VALUE)")),
+    }
+}
+
+/// Parse an age such as `12h`, `30d` or `2w`; plain numbers are days
+pub fn parse_age(value: &str) -> Result<chrono::Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| format!("invalid age '{value}' (expected e.g. 30d, 2w or 12h)"))?;
+    match unit {
+        "h" => Ok(chrono::Duration::hours(amount)),
+        "" | "d" => Ok(chrono::Duration::days(amount)),
+        "w" => Ok(chrono::Duration::weeks(amount)),
+        _ => Err(format!(
+            "unknown unit '{unit}' in age '{value}' (expected h, d or w)"
+        )),
     }
 }
@@ -3,12 +3,18 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use tokio::fs;
 
+use crate::error::ClaudeForgeError;
+use crate::git::GitBackendKind;
+use crate::template::Template;
+
 /// User configuration structure
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Config {
     #[serde(default)]
     pub defaults: Defaults,
     #[serde(default)]
+    pub git: GitConfig,
+    #[serde(default)]
     pub templates: TemplateConfig,
 }
 
@@ -19,12 +25,38 @@ pub struct Defaults {
     pub default_directory: Option<PathBuf>,
 }
 
+/// How template repositories are fetched
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct GitConfig {
+    /// `libgit2` (built in) or `cli` (the system `git` executable)
+    pub backend: GitBackendKind,
+    // Credentials for private repositories with the libgit2 backend, tried
+    // after the SSH agent and before the git credential helper
+    /// SSH private keys to try instead of the default `~/.ssh` keys
+    pub ssh_keys: Vec<PathBuf>,
+    /// Access token for HTTPS repositories, `CLAUDEFORGE_GIT_TOKEN` takes precedence
+    pub token: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(default)]
 pub struct TemplateConfig {
     pub cache_directory: Option<PathBuf>,
     pub auto_update: bool,
     pub update_interval_days: u32,
+    /// Number of templates `claudeforge update` refreshes at the same time
+    pub update_concurrency: usize,
+    /// Never touch the network, only use cached templates
+    pub offline: bool,
+    /// External registry file (TOML or JSON) merged over the built-in templates
+    pub registry: Option<PathBuf>,
+    /// URLs of remote template indexes (TOML or JSON) merged into the registry
+    pub indexes: Vec<String>,
+    /// Repository URL rewrites, e.g. to use an internal mirror
+    pub url_rewrite: Vec<UrlRewrite>,
+    /// User-defined templates managed with `claudeforge template add/remove`
+    pub custom: Vec<Template>,
 }
 
 impl Default for TemplateConfig {
@@ -33,10 +65,35 @@ impl Default for TemplateConfig {
             cache_directory: None,
             auto_update: true,
             update_interval_days: 7,
+            update_concurrency: 4,
+            offline: false,
+            registry: None,
+            indexes: Vec::new(),
+            url_rewrite: Vec::new(),
+            custom: Vec::new(),
         }
     }
 }
 
+impl TemplateConfig {
+    /// Apply the `url_rewrite` rule with the longest matching prefix to a
+    /// repository URL, returning `None` if no rule matches
+    pub fn rewrite_url(&self, url: &str) -> Option<String> {
+        self.url_rewrite
+            .iter()
+            .filter(|rule| url.starts_with(&rule.prefix))
+            .max_by_key(|rule| rule.prefix.len())
+            .map(|rule| format!("{}{}", rule.replacement, &url[rule.prefix.len()..]))
+    }
+}
+
+/// Replace the `prefix` of template repository URLs, like git's `insteadOf`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrlRewrite {
+    pub prefix: String,
+    pub replacement: String,
+}
+
 impl Config {
     /// Load configuration from file, creating default if it doesn't exist
     pub async fn load() -> Result<Self> {
@@ -68,6 +125,28 @@ impl Config {
         Ok(())
     }
 
+    /// Add a custom template, rejecting ids that are already taken
+    pub fn add_custom_template(&mut self, template: Template) -> Result<()> {
+        if self.get_custom_template(&template.id).is_some() {
+            return Err(ClaudeForgeError::DuplicateTemplate(template.id).into());
+        }
+
+        self.templates.custom.push(template);
+        Ok(())
+    }
+
+    /// Remove a custom template by id, returning whether it existed
+    pub fn remove_custom_template(&mut self, id: &str) -> bool {
+        let before = self.templates.custom.len();
+        self.templates.custom.retain(|t| t.id != id);
+        self.templates.custom.len() != before
+    }
+
+    /// Get a custom template by id
+    pub fn get_custom_template(&self, id: &str) -> Option<&Template> {
+        self.templates.custom.iter().find(|t| t.id == id)
+    }
+
     /// Get the effective cache directory
     pub fn cache_directory(&self) -> Result<PathBuf> {
         if let Some(cache_dir) = &self.templates.cache_directory {
@@ -115,6 +194,7 @@ mod tests {
         assert!(config.defaults.author_email.is_none());
         assert!(config.defaults.default_directory.is_none());
         assert!(config.templates.cache_directory.is_none());
+        assert!(config.templates.registry.is_none());
     }
 
     #[tokio::test]
@@ -141,10 +221,17 @@ mod tests {
                 author_email: Some("test@example.com".to_string()),
                 default_directory: Some("/tmp/test".into()),
             },
+            git: GitConfig::default(),
             templates: TemplateConfig {
                 cache_directory: Some("/tmp/cache".into()),
                 auto_update: false,
                 update_interval_days: 30,
+                update_concurrency: 4,
+                offline: false,
+                registry: Some("/tmp/registry.toml".into()),
+                indexes: Vec::new(),
+                url_rewrite: Vec::new(),
+                custom: Vec::new(),
             },
         };
 
@@ -175,6 +262,101 @@ mod tests {
             config.templates.update_interval_days,
             deserialized.templates.update_interval_days
         );
+        assert_eq!(config.templates.registry, deserialized.templates.registry);
+    }
+
+    fn custom_template(id: &str) -> Template {
+        Template {
+            id: id.to_string(),
+            name: id.to_string(),
+            language: "python".to_string(),
+            repository: "https://github.com/user/python-template".to_string(),
+            description: "Test template".to_string(),
+            ..Template::default()
+        }
+    }
+
+    #[test]
+    fn test_add_and_remove_custom_template() {
+        let mut config = Config::default();
+        config
+            .add_custom_template(custom_template("flask-api"))
+            .unwrap();
+        assert_eq!(config.templates.custom.len(), 1);
+        assert!(config.get_custom_template("flask-api").is_some());
+
+        assert!(config.remove_custom_template("flask-api"));
+        assert!(!config.remove_custom_template("flask-api"));
+        assert!(config.templates.custom.is_empty());
+    }
+
+    #[test]
+    fn test_add_duplicate_custom_template() {
+        let mut config = Config::default();
+        config
+            .add_custom_template(custom_template("flask-api"))
+            .unwrap();
+
+        let err = config
+            .add_custom_template(custom_template("flask-api"))
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ClaudeForgeError>(),
+            Some(ClaudeForgeError::DuplicateTemplate(_))
+        ));
+    }
+
+    #[test]
+    fn test_custom_templates_round_trip() {
+        let mut config = Config::default();
+        config
+            .add_custom_template(custom_template("flask-api"))
+            .unwrap();
+
+        let serialized = toml::to_string_pretty(&config).unwrap();
+        assert!(serialized.contains("[[templates.custom]]"));
+
+        let deserialized: Config = toml::from_str(&serialized).unwrap();
+        let template = deserialized.get_custom_template("flask-api").unwrap();
+        assert_eq!(
+            template.repository,
+            "https://github.com/user/python-template"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_url_uses_longest_prefix() {
+        let config: Config = toml::from_str(
+            r#"
+[[templates.url_rewrite]]
+prefix = "https://github.com/"
+replacement = "https://git.corp.example.com/github/"
+
+[[templates.url_rewrite]]
+prefix = "https://github.com/iepathos/"
+replacement = "ssh://git@git.corp.example.com/mirrors/"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config
+                .templates
+                .rewrite_url("https://github.com/iepathos/rust-claude-code")
+                .as_deref(),
+            Some("ssh://git@git.corp.example.com/mirrors/rust-claude-code")
+        );
+        assert_eq!(
+            config
+                .templates
+                .rewrite_url("https://github.com/user/template")
+                .as_deref(),
+            Some("https://git.corp.example.com/github/user/template")
+        );
+        assert!(config
+            .templates
+            .rewrite_url("https://gitlab.com/user/template")
+            .is_none());
     }
 
     #[tokio::test]
@@ -196,10 +378,17 @@ mod tests {
                 author_email: None,
                 default_directory: None,
             },
+            git: GitConfig::default(),
             templates: TemplateConfig {
                 cache_directory: Some("/tmp/custom-cache".into()),
                 auto_update: true,
                 update_interval_days: 7,
+                update_concurrency: 4,
+                offline: false,
+                registry: None,
+                indexes: Vec::new(),
+                url_rewrite: Vec::new(),
+                custom: Vec::new(),
             },
         };
 
@@ -226,10 +415,17 @@ mod tests {
                 author_email: Some("test@example.com".to_string()),
                 default_directory: None,
             },
+            git: GitConfig::default(),
             templates: TemplateConfig {
                 cache_directory: None,
                 auto_update: false,
                 update_interval_days: 14,
+                update_concurrency: 4,
+                offline: false,
+                registry: None,
+                indexes: Vec::new(),
+                url_rewrite: Vec::new(),
+                custom: Vec::new(),
             },
         };
 
@@ -6,9 +6,30 @@ pub enum ClaudeForgeError {
     #[error("Template not found: {0}")]
     TemplateNotFound(String),
 
+    #[error("Local template not found: {0}")]
+    LocalTemplateNotFound(PathBuf),
+
     #[error("Failed to clone repository: {0}")]
     GitCloneError(String),
 
+    #[error("Authentication failed for {url}: {message} (use the SSH agent, an SSH key, a token in CLAUDEFORGE_GIT_TOKEN or a git credential helper)")]
+    GitAuthFailed { url: String, message: String },
+
+    #[error("Repository not found: {0}")]
+    GitRepositoryNotFound(String),
+
+    #[error("Git ref not found: {0} (expected a branch, tag or commit SHA)")]
+    GitRefNotFound(String),
+
+    #[error("Template {0} is not a git repository and cannot be pinned to a ref")]
+    RefNotSupported(String),
+
+    #[error("Subdirectory {subdirectory} not found in template {template}")]
+    SubdirectoryNotFound {
+        template: String,
+        subdirectory: String,
+    },
+
     #[error("Directory already exists: {0}")]
     DirectoryExists(PathBuf),
 
@@ -24,6 +45,84 @@ pub enum ClaudeForgeError {
     #[error("Configuration error: {0}")]
     ConfigError(String),
 
-    #[error("Git not available: Please install git and try again")]
+    #[error("Invalid template registry {path:?}: {message}")]
+    RegistryError { path: PathBuf, message: String },
+
+    #[error("Invalid template index {url}: {message}")]
+    IndexError { url: String, message: String },
+
+    #[error("Invalid template manifest {path:?}: {message}")]
+    InvalidManifest { path: PathBuf, message: String },
+
+    #[error("Invalid variables file {path:?}: {message}")]
+    InvalidVarsFile { path: PathBuf, message: String },
+
+    #[error("Variable '{0}' is required")]
+    VariableRequired(String),
+
+    #[error("Variable '{name}' must be {expected}, got '{value}'")]
+    VariableType {
+        name: String,
+        expected: &'static str,
+        value: String,
+    },
+
+    #[error("Variable '{name}' must be one of {choices}, got '{value}'")]
+    VariableChoice {
+        name: String,
+        choices: String,
+        value: String,
+    },
+
+    #[error("Variable '{name}' must match the pattern '{pattern}', got '{value}'")]
+    VariablePattern {
+        name: String,
+        pattern: String,
+        value: String,
+    },
+
+    #[error("Variable '{name}' is below its min of {min} ({measure} is {actual})")]
+    VariableMin {
+        name: String,
+        min: i64,
+        measure: &'static str,
+        actual: i64,
+    },
+
+    #[error("Variable '{name}' is above its max of {max} ({measure} is {actual})")]
+    VariableMax {
+        name: String,
+        max: i64,
+        measure: &'static str,
+        actual: i64,
+    },
+
+    #[error("Template '{0}' is not cached and cannot be fetched in offline mode; run `claudeforge new` or `claudeforge update` while online first")]
+    TemplateNotCached(String),
+
+    #[error("{0} cached template(s) could not be updated")]
+    UpdateFailed(usize),
+
+    #[error("Template syntax error in {path:?} at line {line}, column {column}: {message}")]
+    TemplateSyntax {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    #[error("Failed to render template {path:?}: {message}")]
+    TemplateRender { path: PathBuf, message: String },
+
+    #[error("Template validation failed: {0}")]
+    TemplateValidation(String),
+
+    #[error("Duplicate template: {0}")]
+    DuplicateTemplate(String),
+
+    #[error("Invalid file customization format: {0}")]
+    InvalidFileCustomization(String),
+
+    #[error("Git not available: install git or switch to the built-in libgit2 backend")]
     GitNotAvailable,
 }
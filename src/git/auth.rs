@@ -0,0 +1,313 @@
+use anyhow::Result;
+use git2::{Cred, CredentialType, ErrorClass, ErrorCode, RemoteCallbacks};
+use std::cell::Cell;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+use crate::config::GitConfig;
+use crate::error::ClaudeForgeError;
+
+/// Environment variable holding an access token for HTTPS repositories
+pub const TOKEN_ENV: &str = "CLAUDEFORGE_GIT_TOKEN";
+
+/// Environment variable holding the passphrase of the SSH key files
+pub const SSH_PASSPHRASE_ENV: &str = "CLAUDEFORGE_SSH_PASSPHRASE";
+
+/// Key files tried when no `ssh_keys` are configured, relative to `~/.ssh`
+const DEFAULT_SSH_KEYS: [&str; 3] = ["id_ed25519", "id_ecdsa", "id_rsa"];
+
+/// Username sent with a token when the URL does not name one. GitHub
+/// ignores it, GitLab and Bitbucket accept any non-empty name for tokens.
+const TOKEN_USERNAME: &str = "x-access-token";
+
+/// Credentials for private template repositories. Each source is tried once,
+/// in order: the SSH agent and SSH key files for SSH remotes, a token and
+/// then the git credential helper for HTTPS remotes.
+#[derive(Clone, Default)]
+pub struct Auth {
+    /// SSH private keys, the default `~/.ssh` keys when empty
+    pub ssh_keys: Vec<PathBuf>,
+    /// Token from the configuration, overridden by `CLAUDEFORGE_GIT_TOKEN`
+    pub token: Option<String>,
+}
+
+impl fmt::Debug for Auth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Auth")
+            .field("ssh_keys", &self.ssh_keys)
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+/// Error of the credentials callback once every source has been tried
+const NO_CREDENTIALS: &str = "no more credentials to try";
+
+/// Credential sources already offered to the remote during one operation
+#[derive(Default)]
+struct Attempts {
+    ssh_agent: bool,
+    ssh_keys: usize,
+    token: bool,
+    credential_helper: bool,
+}
+
+impl Auth {
+    pub fn from_config(config: &GitConfig) -> Self {
+        Self {
+            ssh_keys: config.ssh_keys.iter().map(|key| expand_home(key)).collect(),
+            token: config.token.clone(),
+        }
+    }
+
+    /// Run a git operation with credential callbacks, reporting failures as
+    /// authentication or not-found errors where the remote tells them apart
+    pub(crate) fn run<T>(
+        &self,
+        repo_url: &str,
+        operation: impl FnOnce(RemoteCallbacks<'_>) -> Result<T, git2::Error>,
+    ) -> Result<T> {
+        let attempts = Cell::new(Attempts::default());
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(|url, username_from_url, allowed| {
+            let mut state = attempts.take();
+            let credential = self.next_credential(&mut state, url, username_from_url, allowed);
+            attempts.set(state);
+            credential
+        });
+
+        operation(callbacks).map_err(|e| classify_error(e, repo_url))
+    }
+
+    fn next_credential(
+        &self,
+        state: &mut Attempts,
+        url: &str,
+        username_from_url: Option<&str>,
+        allowed: CredentialType,
+    ) -> Result<Cred, git2::Error> {
+        if allowed.contains(CredentialType::USERNAME) {
+            return Cred::username(username_from_url.unwrap_or("git"));
+        }
+
+        if allowed.contains(CredentialType::SSH_KEY) {
+            let username = username_from_url.unwrap_or("git");
+            if !state.ssh_agent {
+                state.ssh_agent = true;
+                if std::env::var_os("SSH_AUTH_SOCK").is_some() {
+                    debug!("Trying SSH agent for {}", url);
+                    return Cred::ssh_key_from_agent(username);
+                }
+            }
+
+            let keys = self.ssh_key_files();
+            let passphrase = std::env::var(SSH_PASSPHRASE_ENV).ok();
+            while state.ssh_keys < keys.len() {
+                let key = &keys[state.ssh_keys];
+                state.ssh_keys += 1;
+                if key.is_file() {
+                    debug!("Trying SSH key {:?} for {}", key, url);
+                    return Cred::ssh_key(username, None, key, passphrase.as_deref());
+                }
+            }
+        }
+
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            if !state.token {
+                state.token = true;
+                let token = std::env::var(TOKEN_ENV).ok().or_else(|| self.token.clone());
+                if let Some(token) = token.filter(|token| !token.is_empty()) {
+                    debug!("Trying access token for {}", url);
+                    let username = username_from_url.unwrap_or(TOKEN_USERNAME);
+                    return Cred::userpass_plaintext(username, &token);
+                }
+            }
+
+            if !state.credential_helper {
+                state.credential_helper = true;
+                if let Ok(config) = git2::Config::open_default() {
+                    if let Ok(credential) = Cred::credential_helper(&config, url, username_from_url)
+                    {
+                        debug!("Trying git credential helper for {}", url);
+                        return Ok(credential);
+                    }
+                }
+            }
+        }
+
+        Err(git2::Error::from_str(NO_CREDENTIALS))
+    }
+
+    fn ssh_key_files(&self) -> Vec<PathBuf> {
+        if !self.ssh_keys.is_empty() {
+            return self.ssh_keys.clone();
+        }
+        dirs::home_dir()
+            .map(|home| {
+                DEFAULT_SSH_KEYS
+                    .iter()
+                    .map(|name| home.join(".ssh").join(name))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Whether a git error means the credentials or the repository are wrong, so
+/// retrying the operation differently cannot help
+pub(crate) fn is_access_error(error: &anyhow::Error) -> bool {
+    matches!(
+        error.downcast_ref::<ClaudeForgeError>(),
+        Some(ClaudeForgeError::GitAuthFailed { .. } | ClaudeForgeError::GitRepositoryNotFound(_))
+    )
+}
+
+fn classify_error(error: git2::Error, repo_url: &str) -> anyhow::Error {
+    let message = error.message().to_ascii_lowercase();
+
+    // libgit2 takes a local path that does not exist for an unknown URL scheme
+    let missing_path = !repo_url.contains("://") && message.contains("unsupported url protocol");
+    let not_found = message.contains("404")
+        || message.contains("repository not found")
+        || missing_path
+        || (error.code() == ErrorCode::NotFound && error.class() == ErrorClass::Os);
+    if not_found {
+        return ClaudeForgeError::GitRepositoryNotFound(repo_url.to_string()).into();
+    }
+
+    let auth_failed = error.code() == ErrorCode::Auth
+        || message.contains(NO_CREDENTIALS)
+        || message.contains("authentication")
+        || message.contains("401")
+        || message.contains("403");
+    if auth_failed {
+        return ClaudeForgeError::GitAuthFailed {
+            url: repo_url.to_string(),
+            message: error.message().to_string(),
+        }
+        .into();
+    }
+
+    error.into()
+}
+
+/// Expand a leading `~` to the home directory
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing::{http_response, serve_http_forever};
+    use std::sync::mpsc;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_from_config_expands_home() {
+        let config = GitConfig {
+            ssh_keys: vec![PathBuf::from("~/.ssh/id_corp")],
+            token: Some("secret".to_string()),
+            ..GitConfig::default()
+        };
+        let auth = Auth::from_config(&config);
+
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(auth.ssh_keys[0], home.join(".ssh/id_corp"));
+        }
+        assert!(!format!("{auth:?}").contains("secret"));
+    }
+
+    #[test]
+    fn test_next_credential_tries_each_source_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let key = temp_dir.path().join("id_test");
+        std::fs::write(&key, "not a real key").unwrap();
+        let auth = Auth {
+            ssh_keys: vec![temp_dir.path().join("missing"), key],
+            token: None,
+        };
+
+        let mut state = Attempts {
+            ssh_agent: true,
+            ..Attempts::default()
+        };
+        let url = "ssh://git@example.com/team/template.git";
+        assert!(auth
+            .next_credential(&mut state, url, Some("git"), CredentialType::SSH_KEY)
+            .is_ok());
+        assert_eq!(state.ssh_keys, 2);
+        assert!(auth
+            .next_credential(&mut state, url, Some("git"), CredentialType::SSH_KEY)
+            .is_err());
+    }
+
+    /// Answer every HTTP request with `status`, asking for basic auth
+    fn serve_status(status: &str) -> (String, mpsc::Receiver<String>) {
+        let response = http_response(status, &[("WWW-Authenticate", "Basic realm=\"git\"")], b"");
+        serve_http_forever(response, "team/template.git")
+    }
+
+    #[test]
+    fn test_clone_reports_auth_failure_and_missing_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let options = crate::git::CloneOptions {
+            auth: Auth {
+                ssh_keys: Vec::new(),
+                token: Some("secret-token".to_string()),
+            },
+            ..Default::default()
+        };
+
+        let (url, requests) = serve_status("401 Unauthorized");
+        let err = crate::git::clone_template(&url, &temp_dir.path().join("a"), None, &options)
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ClaudeForgeError>(),
+            Some(ClaudeForgeError::GitAuthFailed { .. })
+        ));
+        assert!(requests
+            .try_iter()
+            .any(|request| request.contains("authorization: basic")));
+
+        let (url, _requests) = serve_status("404 Not Found");
+        let err = crate::git::clone_template(&url, &temp_dir.path().join("b"), None, &options)
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ClaudeForgeError>(),
+            Some(ClaudeForgeError::GitRepositoryNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_classify_error() {
+        let auth = git2::Error::new(ErrorCode::Auth, ErrorClass::Http, "authentication required");
+        let err = classify_error(auth, "https://example.com/private.git");
+        assert!(matches!(
+            err.downcast_ref::<ClaudeForgeError>(),
+            Some(ClaudeForgeError::GitAuthFailed { .. })
+        ));
+
+        let missing = git2::Error::new(
+            ErrorCode::GenericError,
+            ErrorClass::Http,
+            "unexpected http status code: 404",
+        );
+        let err = classify_error(missing, "https://example.com/missing.git");
+        assert!(matches!(
+            err.downcast_ref::<ClaudeForgeError>(),
+            Some(ClaudeForgeError::GitRepositoryNotFound(_))
+        ));
+        assert!(is_access_error(&err));
+
+        let other = git2::Error::new(ErrorCode::GenericError, ErrorClass::Net, "timed out");
+        assert!(!is_access_error(&classify_error(
+            other,
+            "https://example.com/x.git"
+        )));
+    }
+}
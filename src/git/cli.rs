@@ -0,0 +1,401 @@
+use anyhow::Result;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tracing::debug;
+
+use super::{auth, ref_refspecs, Auth, CloneOptions, GitBackend};
+use crate::error::ClaudeForgeError;
+
+/// Runs the system `git` executable, so its configuration, credential helpers
+/// and SSH setup apply unchanged. The `[git]` credentials are not used.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CliBackend;
+
+impl GitBackend for CliBackend {
+    fn clone_template(
+        &self,
+        repo_url: &str,
+        target_path: &Path,
+        git_ref: Option<&str>,
+        options: &CloneOptions,
+    ) -> Result<String> {
+        let target = target_path.to_string_lossy();
+
+        if options.shallow {
+            // `--branch` takes branches and tags, commit SHAs need the full clone
+            let mut args = vec!["clone", "--quiet", "--no-checkout", "--depth", "1"];
+            if let Some(git_ref) = git_ref {
+                args.extend(["--branch", git_ref]);
+            }
+            args.extend(["--", repo_url, &target]);
+
+            match git(None, repo_url, &args) {
+                Ok(_) => {
+                    return checkout(target_path, repo_url, "HEAD", git_ref.is_some(), options)
+                }
+                Err(e) if auth::is_access_error(&e) => return Err(e),
+                Err(e) => {
+                    debug!(
+                        "Shallow clone of {} failed, cloning full history: {:#}",
+                        repo_url, e
+                    );
+                    if target_path.exists() {
+                        std::fs::remove_dir_all(target_path)?;
+                    }
+                }
+            }
+        }
+
+        debug!("Cloning repository: {} to {:?}", repo_url, target_path);
+        git(
+            None,
+            repo_url,
+            &["clone", "--quiet", "--no-checkout", "--", repo_url, &target],
+        )?;
+
+        match git_ref {
+            Some(git_ref) => {
+                let commit = resolve_ref(target_path, repo_url, git_ref)?;
+                checkout(target_path, repo_url, &commit, true, options)
+            }
+            None => checkout(target_path, repo_url, "HEAD", false, options),
+        }
+    }
+
+    fn update_repository(
+        &self,
+        repo_path: &Path,
+        repo_url: &str,
+        git_ref: Option<&str>,
+        options: &CloneOptions,
+    ) -> Result<String> {
+        debug!("Updating repository at {:?} from {}", repo_path, repo_url);
+
+        // Follow the template if its repository moved
+        git(
+            Some(repo_path),
+            repo_url,
+            &["remote", "set-url", "origin", repo_url],
+        )?;
+
+        // Shallow clones stay shallow
+        let shallow = git(
+            Some(repo_path),
+            repo_url,
+            &["rev-parse", "--is-shallow-repository"],
+        )? == "true";
+        let mut args = vec!["fetch", "--quiet"];
+        let refspecs = match (shallow, git_ref) {
+            (true, Some(git_ref)) => ref_refspecs(git_ref).to_vec(),
+            _ => vec!["+refs/heads/*:refs/remotes/origin/*".to_string()],
+        };
+        if shallow {
+            args.extend(["--depth", "1"]);
+        } else {
+            args.extend(["--prune", "--tags"]);
+        }
+        args.push("origin");
+        args.extend(refspecs.iter().map(String::as_str));
+        git(Some(repo_path), repo_url, &args)?;
+
+        if let Some(git_ref) = git_ref {
+            let commit = resolve_ref(repo_path, repo_url, git_ref)?;
+            return checkout(repo_path, repo_url, &commit, true, options);
+        }
+
+        let default_branch = git(
+            Some(repo_path),
+            repo_url,
+            &["symbolic-ref", "--quiet", "refs/remotes/origin/HEAD"],
+        )
+        .or_else(|_| {
+            git(
+                Some(repo_path),
+                repo_url,
+                &["symbolic-ref", "--quiet", "--short", "HEAD"],
+            )
+            .map(|branch| format!("refs/remotes/origin/{branch}"))
+        })
+        .map_err(|_| ClaudeForgeError::GitRefNotFound("origin/HEAD".to_string()))?;
+
+        // Discards local changes, the cache only ever mirrors the remote
+        checkout(repo_path, repo_url, &default_branch, false, options)
+    }
+
+    fn check_remote_access(&self, repo_url: &str, _auth: &Auth) -> Result<()> {
+        debug!("Checking access to repository: {}", repo_url);
+        git(
+            None,
+            repo_url,
+            &["ls-remote", "--quiet", "--", repo_url, "HEAD"],
+        )?;
+        Ok(())
+    }
+}
+
+/// Resolve a branch, tag or commit SHA of a cloned repository to a commit id
+fn resolve_ref(repo_path: &Path, repo_url: &str, git_ref: &str) -> Result<String> {
+    // Branches only exist as remote-tracking refs after a clone
+    let candidates = [
+        format!("refs/remotes/origin/{git_ref}"),
+        format!("refs/tags/{git_ref}"),
+        git_ref.to_string(),
+    ];
+    candidates
+        .iter()
+        .find_map(|spec| {
+            let spec = format!("{spec}^{{commit}}");
+            git(
+                Some(repo_path),
+                repo_url,
+                &["rev-parse", "--verify", "--quiet", &spec],
+            )
+            .ok()
+        })
+        .ok_or_else(|| ClaudeForgeError::GitRefNotFound(git_ref.to_string()).into())
+}
+
+/// Force the working tree to `rev`, either detached or by resetting the
+/// current branch, limited to the sparse path if there is one. Returns the id
+/// of the checked out commit.
+fn checkout(
+    repo_path: &Path,
+    repo_url: &str,
+    rev: &str,
+    detach: bool,
+    options: &CloneOptions,
+) -> Result<String> {
+    if let Some(path) = &options.sparse_path {
+        let pattern = format!("/{}/\n", path.to_string_lossy().trim_matches('/'));
+        std::fs::write(repo_path.join(".git/info/sparse-checkout"), pattern)?;
+        git(
+            Some(repo_path),
+            repo_url,
+            &["config", "core.sparseCheckout", "true"],
+        )?;
+    }
+
+    if detach {
+        git(
+            Some(repo_path),
+            repo_url,
+            &[
+                "-c",
+                "advice.detachedHead=false",
+                "checkout",
+                "--quiet",
+                "--force",
+                "--detach",
+                rev,
+            ],
+        )?;
+    } else {
+        git(
+            Some(repo_path),
+            repo_url,
+            &["reset", "--quiet", "--hard", rev],
+        )?;
+    }
+
+    let commit = git(Some(repo_path), repo_url, &["rev-parse", "HEAD"])?;
+    debug!("Checked out {} at {}", rev, commit);
+    Ok(commit)
+}
+
+/// Run `git` with `args`, in `repo_path` if given, returning its trimmed
+/// standard output. Failures are reported as authentication or not-found
+/// errors where git's message tells them apart.
+fn git(repo_path: Option<&Path>, repo_url: &str, args: &[&str]) -> Result<String> {
+    let mut command = Command::new("git");
+    if let Some(repo_path) = repo_path {
+        command.arg("-C").arg(repo_path);
+    }
+    let output = command
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => ClaudeForgeError::GitNotAvailable,
+            _ => ClaudeForgeError::IoError(e),
+        })?;
+
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).trim().to_string());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    Err(classify_error(&stderr, repo_url, args))
+}
+
+fn classify_error(stderr: &str, repo_url: &str, args: &[&str]) -> anyhow::Error {
+    let message = stderr.to_ascii_lowercase();
+
+    let not_found = message.contains("repository not found")
+        || message.contains("does not appear to be a git repository")
+        || message.contains("does not exist")
+        || message.contains("404");
+    if not_found {
+        return ClaudeForgeError::GitRepositoryNotFound(repo_url.to_string()).into();
+    }
+
+    let auth_failed = message.contains("authentication failed")
+        || message.contains("could not read username")
+        || message.contains("could not read password")
+        || message.contains("permission denied")
+        || message.contains("terminal prompts disabled");
+    if auth_failed {
+        return ClaudeForgeError::GitAuthFailed {
+            url: repo_url.to_string(),
+            message: stderr.to_string(),
+        }
+        .into();
+    }
+
+    anyhow::anyhow!("git {} failed: {}", args.join(" "), stderr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{add_all_and_commit, init_repository, is_git_available};
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    /// Repository with a `v1` tag, a second commit and a `docs` directory
+    fn origin_repository(root: &Path) -> PathBuf {
+        let origin = root.join("origin");
+        std::fs::create_dir_all(origin.join("docs")).unwrap();
+        std::fs::write(origin.join("VERSION"), "1").unwrap();
+        std::fs::write(origin.join("docs").join("README.md"), "docs").unwrap();
+        init_repository(&origin).unwrap();
+        add_all_and_commit(&origin, "v1").unwrap();
+        git(Some(&origin), "origin", &["tag", "v1"]).unwrap();
+        std::fs::write(origin.join("VERSION"), "2").unwrap();
+        git(Some(&origin), "origin", &["add", "VERSION"]).unwrap();
+        git(
+            Some(&origin),
+            "origin",
+            &[
+                "-c",
+                "user.name=Test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "--quiet",
+                "-m",
+                "v2",
+            ],
+        )
+        .unwrap();
+        origin
+    }
+
+    #[test]
+    fn test_cli_clone_and_update() {
+        if !is_git_available() {
+            eprintln!("git is not available, skipping");
+            return;
+        }
+        let temp_dir = TempDir::new().unwrap();
+        let origin = origin_repository(temp_dir.path());
+        let url = origin.to_string_lossy().to_string();
+        let backend = CliBackend;
+
+        let latest = temp_dir.path().join("latest");
+        let head = backend
+            .clone_template(&url, &latest, None, &CloneOptions::default())
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(latest.join("VERSION")).unwrap(),
+            "2"
+        );
+        assert_eq!(crate::git::head_commit(&latest), Some(head));
+
+        let pinned = temp_dir.path().join("pinned");
+        backend
+            .clone_template(&url, &pinned, Some("v1"), &CloneOptions::default())
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(pinned.join("VERSION")).unwrap(),
+            "1"
+        );
+
+        let sparse = temp_dir.path().join("sparse");
+        let options = CloneOptions {
+            sparse_path: Some(PathBuf::from("docs")),
+            ..CloneOptions::default()
+        };
+        backend
+            .clone_template(&url, &sparse, None, &options)
+            .unwrap();
+        assert!(sparse.join("docs/README.md").exists());
+        assert!(!sparse.join("VERSION").exists());
+
+        std::fs::write(origin.join("VERSION"), "3").unwrap();
+        git(Some(&origin), &url, &["add", "VERSION"]).unwrap();
+        git(
+            Some(&origin),
+            &url,
+            &[
+                "-c",
+                "user.name=Test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "--quiet",
+                "-m",
+                "v3",
+            ],
+        )
+        .unwrap();
+        backend
+            .update_repository(&latest, &url, None, &CloneOptions::default())
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(latest.join("VERSION")).unwrap(),
+            "3"
+        );
+    }
+
+    #[test]
+    fn test_cli_reports_missing_repository_and_ref() {
+        if !is_git_available() {
+            eprintln!("git is not available, skipping");
+            return;
+        }
+        let temp_dir = TempDir::new().unwrap();
+        let origin = origin_repository(temp_dir.path());
+        let url = origin.to_string_lossy().to_string();
+        let backend = CliBackend;
+
+        let err = backend
+            .clone_template(
+                &format!("{url}-missing"),
+                &temp_dir.path().join("a"),
+                None,
+                &CloneOptions::default(),
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ClaudeForgeError>(),
+            Some(ClaudeForgeError::GitRepositoryNotFound(_))
+        ));
+        assert!(backend
+            .check_remote_access(&format!("{url}-missing"), &Auth::default())
+            .is_err());
+        assert!(backend.check_remote_access(&url, &Auth::default()).is_ok());
+
+        let err = backend
+            .clone_template(
+                &url,
+                &temp_dir.path().join("b"),
+                Some("v9"),
+                &CloneOptions::default(),
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ClaudeForgeError>(),
+            Some(ClaudeForgeError::GitRefNotFound(_))
+        ));
+    }
+}
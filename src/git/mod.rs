@@ -1,16 +1,331 @@
 use anyhow::{Context, Result};
-use git2::{Repository, Signature};
-use std::path::Path;
+use git2::build::{CheckoutBuilder, RepoBuilder};
+use git2::{FetchOptions, Repository, Signature};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tracing::{debug, info};
 
+use crate::error::ClaudeForgeError;
+
+pub mod auth;
+pub mod cli;
+
+pub use auth::Auth;
+pub use cli::CliBackend;
+
+/// Which implementation performs git operations on template repositories
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitBackendKind {
+    /// The linked libgit2, needs no `git` executable
+    #[default]
+    Libgit2,
+    /// The system `git` executable, with the user's own git setup
+    Cli,
+}
+
+/// Git operations on template repositories
+pub trait GitBackend: Send + Sync {
+    /// Clone a repository and check out `git_ref`, or the default branch,
+    /// returning the id of the checked out commit
+    fn clone_template(
+        &self,
+        repo_url: &str,
+        target_path: &Path,
+        git_ref: Option<&str>,
+        options: &CloneOptions,
+    ) -> Result<String>;
+
+    /// Fetch into an existing clone and reset it to `git_ref`, or the
+    /// remote's default branch, returning the id of the checked out commit
+    fn update_repository(
+        &self,
+        repo_path: &Path,
+        repo_url: &str,
+        git_ref: Option<&str>,
+        options: &CloneOptions,
+    ) -> Result<String>;
+
+    /// Check that a remote repository can be reached, like `git ls-remote`
+    fn check_remote_access(&self, repo_url: &str, auth: &Auth) -> Result<()>;
+}
+
+/// Performs git operations with the linked libgit2
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Libgit2Backend;
+
+impl GitBackend for Libgit2Backend {
+    fn clone_template(
+        &self,
+        repo_url: &str,
+        target_path: &Path,
+        git_ref: Option<&str>,
+        options: &CloneOptions,
+    ) -> Result<String> {
+        clone_template(repo_url, target_path, git_ref, options)
+    }
+
+    fn update_repository(
+        &self,
+        repo_path: &Path,
+        repo_url: &str,
+        git_ref: Option<&str>,
+        options: &CloneOptions,
+    ) -> Result<String> {
+        update_repository(repo_path, repo_url, git_ref, options)
+    }
+
+    fn check_remote_access(&self, repo_url: &str, auth: &Auth) -> Result<()> {
+        check_remote_access(repo_url, auth)
+    }
+}
+
+/// The git backend selected in the configuration
+pub fn backend(kind: GitBackendKind) -> Arc<dyn GitBackend> {
+    match kind {
+        GitBackendKind::Libgit2 => Arc::new(Libgit2Backend),
+        GitBackendKind::Cli => Arc::new(CliBackend),
+    }
+}
+
 /// Clone a repository to a target path
 pub fn clone_repository(repo_url: &str, target_path: &Path) -> Result<()> {
-    debug!("Cloning repository: {} to {:?}", repo_url, target_path);
+    clone_template(repo_url, target_path, None, &CloneOptions::default())?;
+
+    info!("Successfully cloned repository to {:?}", target_path);
+    Ok(())
+}
+
+/// How much of a template repository to fetch and check out
+#[derive(Debug, Clone, Default)]
+pub struct CloneOptions {
+    /// Fetch only the tip commit instead of the full history
+    pub shallow: bool,
+    /// Check out only this path of the repository
+    pub sparse_path: Option<PathBuf>,
+    /// Credentials for private repositories
+    pub auth: Auth,
+}
+
+impl CloneOptions {
+    fn checkout_builder(&self) -> CheckoutBuilder<'static> {
+        let mut checkout = CheckoutBuilder::new();
+        checkout.force();
+        if let Some(path) = &self.sparse_path {
+            checkout.path(path.clone());
+        }
+        checkout
+    }
+}
 
-    Repository::clone(repo_url, target_path)
+/// Clone a template repository and check out `git_ref`, or the default
+/// branch, returning the id of the checked out commit. Shallow clones fall
+/// back to a full clone when the remote or the ref does not allow them.
+pub fn clone_template(
+    repo_url: &str,
+    target_path: &Path,
+    git_ref: Option<&str>,
+    options: &CloneOptions,
+) -> Result<String> {
+    if options.shallow {
+        match shallow_clone(repo_url, target_path, git_ref, options) {
+            Ok(commit) => return Ok(commit),
+            Err(e) if auth::is_access_error(&e) => return Err(e),
+            Err(e) => {
+                debug!(
+                    "Shallow clone of {} failed, cloning full history: {:#}",
+                    repo_url, e
+                );
+                if target_path.exists() {
+                    std::fs::remove_dir_all(target_path)?;
+                }
+            }
+        }
+    }
+
+    debug!("Cloning repository: {} to {:?}", repo_url, target_path);
+    let repo = options
+        .auth
+        .run(repo_url, |callbacks| {
+            let mut fetch_options = FetchOptions::new();
+            fetch_options.remote_callbacks(callbacks);
+            RepoBuilder::new()
+                .fetch_options(fetch_options)
+                .with_checkout(options.checkout_builder())
+                .clone(repo_url, target_path)
+        })
         .with_context(|| format!("Failed to clone repository: {repo_url}"))?;
 
-    info!("Successfully cloned repository to {:?}", target_path);
+    match git_ref {
+        Some(git_ref) => checkout_ref_with(target_path, git_ref, options),
+        None => Ok(repo.head()?.peel_to_commit()?.id().to_string()),
+    }
+}
+
+/// Fetch only the tip of the default branch, or of the branch or tag `git_ref`
+fn shallow_clone(
+    repo_url: &str,
+    target_path: &Path,
+    git_ref: Option<&str>,
+    options: &CloneOptions,
+) -> Result<String> {
+    let Some(git_ref) = git_ref else {
+        let repo = options.auth.run(repo_url, |callbacks| {
+            let mut fetch_options = FetchOptions::new();
+            fetch_options.depth(1).remote_callbacks(callbacks);
+            RepoBuilder::new()
+                .fetch_options(fetch_options)
+                .with_checkout(options.checkout_builder())
+                .clone(repo_url, target_path)
+        })?;
+        return Ok(repo.head()?.peel_to_commit()?.id().to_string());
+    };
+
+    // Commit SHAs cannot be fetched by name and end up in the full clone fallback
+    let repo = Repository::init(target_path)?;
+    let mut remote = repo.remote("origin", repo_url)?;
+    options.auth.run(repo_url, |callbacks| {
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.depth(1).remote_callbacks(callbacks);
+        remote.fetch(&ref_refspecs(git_ref), Some(&mut fetch_options), None)
+    })?;
+    checkout_ref_with(target_path, git_ref, options)
+}
+
+/// Refspecs fetching just the branch or tag `git_ref`
+fn ref_refspecs(git_ref: &str) -> [String; 2] {
+    [
+        format!("+refs/heads/{git_ref}:refs/remotes/origin/{git_ref}"),
+        format!("+refs/tags/{git_ref}:refs/tags/{git_ref}"),
+    ]
+}
+
+/// Check out a branch, tag or commit SHA in a freshly cloned repository,
+/// returning the id of the checked out commit
+pub fn checkout_ref(repo_path: &Path, git_ref: &str) -> Result<String> {
+    checkout_ref_with(repo_path, git_ref, &CloneOptions::default())
+}
+
+fn checkout_ref_with(repo_path: &Path, git_ref: &str, options: &CloneOptions) -> Result<String> {
+    let repo = Repository::open(repo_path)
+        .with_context(|| format!("Failed to open git repository at {repo_path:?}"))?;
+
+    // Branches only exist as remote-tracking refs after a clone
+    let candidates = [
+        format!("refs/remotes/origin/{git_ref}"),
+        format!("refs/tags/{git_ref}"),
+        git_ref.to_string(),
+    ];
+    let object = candidates
+        .iter()
+        .find_map(|spec| repo.revparse_single(spec).ok())
+        .ok_or_else(|| ClaudeForgeError::GitRefNotFound(git_ref.to_string()))?;
+    let commit = object.peel_to_commit()?;
+
+    repo.checkout_tree(commit.as_object(), Some(&mut options.checkout_builder()))?;
+    repo.set_head_detached(commit.id())?;
+
+    debug!("Checked out {} at {}", git_ref, commit.id());
+    Ok(commit.id().to_string())
+}
+
+/// Id of the commit checked out in a repository
+pub fn head_commit(repo_path: &Path) -> Option<String> {
+    let repo = Repository::open(repo_path).ok()?;
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    Some(commit.id().to_string())
+}
+
+/// Whether a directory holds a usable clone with an `origin` remote
+pub fn is_valid_clone(repo_path: &Path) -> bool {
+    Repository::open(repo_path)
+        .and_then(|repo| repo.find_remote("origin").map(|_| ()))
+        .is_ok()
+}
+
+/// Bring an existing clone up to date by fetching from `origin` and resetting
+/// to the given ref, or to the remote's default branch. Returns the id of the
+/// checked out commit.
+pub fn update_repository(
+    repo_path: &Path,
+    repo_url: &str,
+    git_ref: Option<&str>,
+    options: &CloneOptions,
+) -> Result<String> {
+    debug!("Updating repository at {:?} from {}", repo_path, repo_url);
+
+    let repo = Repository::open(repo_path)
+        .with_context(|| format!("Failed to open git repository at {repo_path:?}"))?;
+
+    // Follow the template if its repository moved
+    repo.remote_set_url("origin", repo_url)?;
+    let mut remote = repo.find_remote("origin")?;
+
+    // Shallow clones stay shallow, local remotes never are
+    let shallow = repo.is_shallow();
+    let refspecs = match (shallow, git_ref) {
+        (true, Some(git_ref)) => ref_refspecs(git_ref).to_vec(),
+        _ => vec!["+refs/heads/*:refs/remotes/origin/*".to_string()],
+    };
+    options
+        .auth
+        .run(repo_url, |callbacks| {
+            let mut fetch_options = FetchOptions::new();
+            fetch_options.remote_callbacks(callbacks);
+            if shallow {
+                fetch_options.depth(1);
+            } else {
+                fetch_options.prune(git2::FetchPrune::On);
+                fetch_options.download_tags(git2::AutotagOption::All);
+            }
+            remote.fetch(&refspecs, Some(&mut fetch_options), None)
+        })
+        .with_context(|| format!("Failed to fetch repository: {repo_url}"))?;
+
+    if let Some(git_ref) = git_ref {
+        return checkout_ref_with(repo_path, git_ref, options);
+    }
+
+    let default_branch = repo
+        .find_reference("refs/remotes/origin/HEAD")
+        .ok()
+        .and_then(|reference| reference.symbolic_target().map(str::to_string))
+        .or_else(|| {
+            let head = repo.head().ok()?;
+            Some(format!("refs/remotes/origin/{}", head.shorthand()?))
+        })
+        .ok_or_else(|| ClaudeForgeError::GitRefNotFound("origin/HEAD".to_string()))?;
+    let commit = repo
+        .find_reference(&default_branch)
+        .map_err(|_| ClaudeForgeError::GitRefNotFound(default_branch.clone()))?
+        .peel_to_commit()?;
+
+    // Discards local changes, the cache only ever mirrors the remote
+    repo.reset(
+        commit.as_object(),
+        git2::ResetType::Hard,
+        Some(&mut options.checkout_builder()),
+    )?;
+
+    info!("Updated {:?} to {}", repo_path, commit.id());
+    Ok(commit.id().to_string())
+}
+
+/// Check that a remote repository can be reached, like `git ls-remote`
+pub fn check_remote_access(repo_url: &str, auth: &Auth) -> Result<()> {
+    debug!("Checking access to repository: {}", repo_url);
+
+    let mut remote = git2::Remote::create_detached(repo_url)
+        .with_context(|| format!("Invalid repository URL: {repo_url}"))?;
+    auth.run(repo_url, |callbacks| {
+        // Dropping the connection disconnects again
+        remote
+            .connect_auth(git2::Direction::Fetch, Some(callbacks), None)
+            .map(drop)
+    })
+    .with_context(|| format!("Cannot access repository: {repo_url}"))?;
+
     Ok(())
 }
 
@@ -65,7 +380,22 @@ fn get_signature(repo: &Repository) -> Result<Signature<'_>> {
     Ok(Signature::now(&name, &email)?)
 }
 
-/// Check if git is available on the system
+/// `user.name` and `user.email` from the global git configuration, read
+/// without running the `git` executable
+pub fn user_identity() -> (Option<String>, Option<String>) {
+    let Ok(config) = git2::Config::open_default() else {
+        return (None, None);
+    };
+    let get = |key| {
+        config
+            .get_string(key)
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+    };
+    (get("user.name"), get("user.email"))
+}
+
+/// Check if the `git` executable is available, which only the CLI backend needs
 pub fn is_git_available() -> bool {
     std::process::Command::new("git")
         .arg("--version")
@@ -80,9 +410,250 @@ mod tests {
     use tempfile::TempDir;
 
     #[test]
-    fn test_git_available() {
-        // This test depends on git being installed
-        assert!(is_git_available());
+    fn test_check_remote_access_local_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        init_repository(temp_dir.path()).unwrap();
+
+        let url = temp_dir.path().to_string_lossy().to_string();
+        assert!(check_remote_access(&url, &Auth::default()).is_ok());
+        let err = check_remote_access(&format!("{url}/missing"), &Auth::default()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ClaudeForgeError>(),
+            Some(ClaudeForgeError::GitRepositoryNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_checkout_ref() {
+        let temp_dir = TempDir::new().unwrap();
+        let origin = temp_dir.path().join("origin");
+        std::fs::create_dir_all(&origin).unwrap();
+
+        init_repository(&origin).unwrap();
+        std::fs::write(origin.join("VERSION"), "1").unwrap();
+        add_all_and_commit(&origin, "v1").unwrap();
+        let repo = Repository::open(&origin).unwrap();
+        let v1 = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.tag_lightweight("v1.0.0", v1.as_object(), false)
+            .unwrap();
+
+        std::fs::write(origin.join("VERSION"), "2").unwrap();
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "v2", &tree, &[&v1])
+            .unwrap();
+
+        let clone = temp_dir.path().join("clone");
+        clone_repository(origin.to_str().unwrap(), &clone).unwrap();
+        assert_eq!(std::fs::read_to_string(clone.join("VERSION")).unwrap(), "2");
+
+        let commit = checkout_ref(&clone, "v1.0.0").unwrap();
+        assert_eq!(commit, v1.id().to_string());
+        assert_eq!(std::fs::read_to_string(clone.join("VERSION")).unwrap(), "1");
+
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        checkout_ref(&clone, &branch).unwrap();
+        assert_eq!(std::fs::read_to_string(clone.join("VERSION")).unwrap(), "2");
+
+        checkout_ref(&clone, &v1.id().to_string()[..10]).unwrap();
+        assert_eq!(std::fs::read_to_string(clone.join("VERSION")).unwrap(), "1");
+
+        let err = checkout_ref(&clone, "does-not-exist").unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ClaudeForgeError>(),
+            Some(ClaudeForgeError::GitRefNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_update_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let origin = temp_dir.path().join("origin");
+        std::fs::create_dir_all(&origin).unwrap();
+        init_repository(&origin).unwrap();
+        std::fs::write(origin.join("VERSION"), "1").unwrap();
+        add_all_and_commit(&origin, "v1").unwrap();
+
+        let url = origin.to_str().unwrap();
+        let clone = temp_dir.path().join("clone");
+        clone_repository(url, &clone).unwrap();
+        assert!(is_valid_clone(&clone));
+        assert!(!is_valid_clone(&origin.join("missing")));
+
+        let repo = Repository::open(&origin).unwrap();
+        let v1 = repo.head().unwrap().peel_to_commit().unwrap();
+        std::fs::write(origin.join("VERSION"), "2").unwrap();
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let v2 = repo
+            .commit(Some("HEAD"), &signature, &signature, "v2", &tree, &[&v1])
+            .unwrap();
+        repo.tag_lightweight("v2.0.0", &repo.find_object(v2, None).unwrap(), false)
+            .unwrap();
+
+        // Local edits in the cache are discarded
+        std::fs::write(clone.join("VERSION"), "edited").unwrap();
+        let commit = update_repository(&clone, url, None, &CloneOptions::default()).unwrap();
+        assert_eq!(commit, v2.to_string());
+        assert_eq!(std::fs::read_to_string(clone.join("VERSION")).unwrap(), "2");
+
+        let commit = update_repository(
+            &clone,
+            url,
+            Some(&v1.id().to_string()),
+            &CloneOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(commit, v1.id().to_string());
+        assert_eq!(std::fs::read_to_string(clone.join("VERSION")).unwrap(), "1");
+
+        update_repository(&clone, url, Some("v2.0.0"), &CloneOptions::default()).unwrap();
+        assert_eq!(std::fs::read_to_string(clone.join("VERSION")).unwrap(), "2");
+    }
+
+    #[test]
+    fn test_clone_template_sparse_and_shallow_fallback() {
+        let temp_dir = TempDir::new().unwrap();
+        let origin = temp_dir.path().join("origin");
+        for name in ["rust-service", "go-worker"] {
+            let dir = origin.join("templates").join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("README.md"), name).unwrap();
+        }
+        init_repository(&origin).unwrap();
+        add_all_and_commit(&origin, "Add templates").unwrap();
+
+        // The local transport cannot fetch shallowly, so this falls back
+        let options = CloneOptions {
+            shallow: true,
+            sparse_path: Some(PathBuf::from("templates/rust-service")),
+            ..CloneOptions::default()
+        };
+        let clone = temp_dir.path().join("clone");
+        clone_template(origin.to_str().unwrap(), &clone, None, &options).unwrap();
+
+        assert!(clone.join("templates/rust-service/README.md").exists());
+        assert!(!clone.join("templates/go-worker").exists());
+
+        update_repository(&clone, origin.to_str().unwrap(), None, &options).unwrap();
+        assert!(clone.join("templates/rust-service/README.md").exists());
+        assert!(!clone.join("templates/go-worker").exists());
+    }
+
+    /// `git daemon` serving a directory over the git protocol, which unlike
+    /// the local transport supports shallow fetches. Stopped when dropped.
+    struct GitDaemon {
+        process: std::process::Child,
+        port: u16,
+    }
+
+    impl GitDaemon {
+        /// Returns `None` when `git daemon` is not available
+        fn start(base_path: &Path) -> Option<Self> {
+            use std::process::{Command, Stdio};
+
+            let port = std::net::TcpListener::bind("127.0.0.1:0")
+                .ok()?
+                .local_addr()
+                .ok()?
+                .port();
+            let process = Command::new("git")
+                .arg("daemon")
+                .arg("--export-all")
+                .arg("--listen=127.0.0.1")
+                .arg(format!("--port={port}"))
+                .arg(format!("--base-path={}", base_path.display()))
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .ok()?;
+            let daemon = Self { process, port };
+
+            for _ in 0..50 {
+                if std::net::TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                    return Some(daemon);
+                }
+                std::thread::sleep(std::time::Duration::from_millis(100));
+            }
+            None
+        }
+    }
+
+    impl Drop for GitDaemon {
+        fn drop(&mut self) {
+            let _ = self.process.kill();
+            let _ = self.process.wait();
+        }
+    }
+
+    #[test]
+    fn test_clone_template_shallow() {
+        let temp_dir = TempDir::new().unwrap();
+        let origin = temp_dir.path().join("origin");
+        std::fs::create_dir_all(&origin).unwrap();
+        init_repository(&origin).unwrap();
+        std::fs::write(origin.join("VERSION"), "1").unwrap();
+        add_all_and_commit(&origin, "v1").unwrap();
+
+        let repo = Repository::open(&origin).unwrap();
+        let v1 = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.tag_lightweight("v1", v1.as_object(), false).unwrap();
+        std::fs::write(origin.join("VERSION"), "2").unwrap();
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "v2", &tree, &[&v1])
+            .unwrap();
+
+        let Some(daemon) = GitDaemon::start(temp_dir.path()) else {
+            eprintln!("git daemon is not available, skipping");
+            return;
+        };
+        let url = format!("git://127.0.0.1:{}/origin", daemon.port);
+        let options = CloneOptions {
+            shallow: true,
+            ..CloneOptions::default()
+        };
+
+        let latest = temp_dir.path().join("latest");
+        clone_template(&url, &latest, None, &options).unwrap();
+        let clone = Repository::open(&latest).unwrap();
+        assert!(clone.is_shallow());
+        assert_eq!(
+            std::fs::read_to_string(latest.join("VERSION")).unwrap(),
+            "2"
+        );
+        update_repository(&latest, &url, None, &options).unwrap();
+        assert!(clone.is_shallow());
+
+        let pinned = temp_dir.path().join("pinned");
+        let commit = clone_template(&url, &pinned, Some("v1"), &options).unwrap();
+        assert_eq!(commit, v1.id().to_string());
+        assert!(Repository::open(&pinned).unwrap().is_shallow());
+        assert_eq!(
+            std::fs::read_to_string(pinned.join("VERSION")).unwrap(),
+            "1"
+        );
+
+        // Commit SHAs fall back to a full clone
+        let by_sha = temp_dir.path().join("by-sha");
+        clone_template(&url, &by_sha, Some(&v1.id().to_string()), &options).unwrap();
+        assert!(!Repository::open(&by_sha).unwrap().is_shallow());
     }
 
     #[test]
@@ -5,6 +5,6 @@ pub mod git;
 pub mod template;
 pub mod utils;
 
-pub use cli::{Cli, Commands, Language};
+pub use cli::{CacheCommands, Cli, Commands, TemplateCommands};
 pub use error::ClaudeForgeError;
 pub use template::processor::create_project;
@@ -1,11 +1,19 @@
 use anyhow::Result;
 use clap::Parser;
-use tracing::{error, info};
+use std::path::PathBuf;
+use tracing::{info, warn};
 
+use claudeforge::config::Config;
 use claudeforge::error::ClaudeForgeError;
-use claudeforge::git;
-use claudeforge::template::loader::TemplateLoader;
-use claudeforge::{create_project, Cli, Commands};
+use claudeforge::template::cache;
+use claudeforge::template::custom;
+use claudeforge::template::engine::Variables;
+use claudeforge::template::index;
+use claudeforge::template::loader::{TemplateLoader, UpdateStatus};
+use claudeforge::template::processor::create_project_with_loader;
+use claudeforge::template::vars;
+use claudeforge::template::Template;
+use claudeforge::{CacheCommands, Cli, Commands, TemplateCommands};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -19,28 +27,96 @@ async fn main() -> Result<()> {
         )
         .init();
 
-    // Check if git is available
-    if !git::is_git_available() {
-        error!("Git is not available on this system");
-        return Err(ClaudeForgeError::GitNotAvailable.into());
-    }
+    // Only commands that need the config read it, so a broken config file
+    // does not stop e.g. `version`
+    let config = || load_config(cli.registry.clone(), cli.offline);
 
     match cli.command {
         Commands::New {
-            language,
+            template,
+            git_ref,
             name,
             directory,
             yes,
+            vars,
+            vars_file,
         } => {
-            info!("Creating new {} project: {}", language, name);
-            create_project(language, name, directory, yes).await?;
+            info!("Creating new {} project: {}", template, name);
+            let variables = match vars_file {
+                Some(path) => vars::load_vars_file(&path).await?,
+                None => Variables::new(),
+            };
+            let variables = vars::merge_vars(variables, vars);
+            let loader = TemplateLoader::from_config(&config().await?).await?;
+            create_project_with_loader(
+                &loader, &template, git_ref, name, directory, yes, variables,
+            )
+            .await?;
         }
         Commands::List => {
-            list_templates().await?;
+            list_templates(&config().await?).await?;
         }
         Commands::Update => {
-            update_templates().await?;
+            update_templates(&config().await?).await?;
         }
+        Commands::Template { command } => match command {
+            TemplateCommands::Add {
+                id,
+                language,
+                repository,
+                git_ref,
+                subdirectory,
+                shallow,
+                sparse,
+                name,
+                description,
+                files,
+            } => {
+                let template = Template {
+                    name: name.unwrap_or_else(|| id.clone()),
+                    description: description
+                        .unwrap_or_else(|| format!("Custom {language} template")),
+                    files_to_customize: custom::parse_file_customizations(&files)?,
+                    variables: Vec::new(),
+                    shallow,
+                    sparse,
+                    id,
+                    language,
+                    repository,
+                    git_ref,
+                    subdirectory,
+                };
+                add_template(template).await?;
+            }
+            TemplateCommands::Remove { id } => {
+                remove_template(&id).await?;
+            }
+            TemplateCommands::Show { id } => {
+                show_template(&config().await?, &id).await?;
+            }
+        },
+        Commands::Cache { command } => match command {
+            CacheCommands::List => {
+                list_cache(&config().await?).await?;
+            }
+            CacheCommands::Clean {
+                template,
+                repository,
+            } => {
+                clean_cache(&config().await?, template.as_deref(), repository).await?;
+            }
+            CacheCommands::Prune { older_than } => {
+                prune_cache(&config().await?, older_than).await?;
+            }
+            CacheCommands::Path => {
+                // Still show where the cache is when the config file is broken
+                let config = config().await.unwrap_or_else(|e| {
+                    warn!("Ignoring the config file: {e:#}");
+                    Config::default()
+                });
+                println!("{}", config.cache_directory()?.display());
+            }
+        },
         Commands::Version => {
             print_version();
         }
@@ -49,26 +125,239 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-async fn list_templates() -> Result<()> {
-    let loader = TemplateLoader::new().await?;
+/// Load the config file with the global command line options applied
+async fn load_config(registry: Option<PathBuf>, offline: bool) -> Result<Config> {
+    let mut config = Config::load().await?;
+    if let Some(registry) = registry {
+        config.templates.registry = Some(registry);
+    }
+    if offline {
+        config.templates.offline = true;
+    }
+    Ok(config)
+}
+
+async fn list_templates(config: &Config) -> Result<()> {
+    let loader = TemplateLoader::from_config(config).await?;
     let templates = loader.list_templates();
 
     println!("Available templates:");
     println!();
 
     for template in templates {
-        println!("  {} ({})", template.name, template.language);
+        println!("  {} ({})", template.id, template.language);
+        println!("    Name: {}", template.name);
         println!("    Description: {}", template.description);
-        println!("    Repository: {}", template.repository);
+        match loader.rewritten_from(&template.id) {
+            Some(original) => println!(
+                "    Repository: {} (rewritten from {original})",
+                template.repository
+            ),
+            None => println!("    Repository: {}", template.repository),
+        }
+        if let Some(git_ref) = &template.git_ref {
+            println!("    Ref: {git_ref}");
+        }
+        if let Some(subdirectory) = &template.subdirectory {
+            println!("    Subdirectory: {subdirectory}");
+        }
         println!();
     }
 
     Ok(())
 }
 
-async fn update_templates() -> Result<()> {
-    let loader = TemplateLoader::new().await?;
-    loader.update_all().await?;
+async fn update_templates(config: &Config) -> Result<()> {
+    let loader = TemplateLoader::from_config(config).await?;
+    let updates = loader.update_all().await?;
+    if updates.is_empty() {
+        return Ok(());
+    }
+
+    println!("{:<32} {:<10} DETAILS", "TEMPLATE", "STATUS");
+    for update in &updates {
+        let (status, details) = match &update.status {
+            UpdateStatus::Updated(commit) => (
+                "updated",
+                commit
+                    .as_deref()
+                    .map_or("", |commit| &commit[..commit.len().min(8)]),
+            ),
+            UpdateStatus::Unchanged => ("unchanged", ""),
+            UpdateStatus::Failed(reason) => ("failed", reason.as_str()),
+        };
+        println!("{:<32} {status:<10} {details}", update.templates.join(", "));
+    }
+
+    let failed = updates.iter().filter(|u| u.status.is_failed()).count();
+    if failed > 0 {
+        return Err(ClaudeForgeError::UpdateFailed(failed).into());
+    }
+    Ok(())
+}
+
+async fn add_template(template: Template) -> Result<()> {
+    // Reload the config so CLI overrides such as --registry are not persisted
+    let mut config = Config::load().await?;
+
+    custom::validate_custom_template(&template, &config).await?;
+
+    let id = template.id.clone();
+    config.add_custom_template(template)?;
+    config.save().await?;
+
+    println!("✅ Custom template '{id}' added successfully");
+    Ok(())
+}
+
+async fn remove_template(id: &str) -> Result<()> {
+    let mut config = Config::load().await?;
+
+    if !config.remove_custom_template(id) {
+        return Err(ClaudeForgeError::TemplateNotFound(id.to_string()).into());
+    }
+    config.save().await?;
+
+    println!("✅ Custom template '{id}' removed successfully");
+    Ok(())
+}
+
+async fn show_template(config: &Config, id: &str) -> Result<()> {
+    let loader = TemplateLoader::from_config(config).await?;
+    let template = loader.get_template(id)?;
+
+    let kind = if config.get_custom_template(id).is_some() {
+        "custom"
+    } else {
+        "built-in"
+    };
+
+    println!("Template: {} ({kind})", template.id);
+    println!("Name: {}", template.name);
+    println!("Language: {}", template.language);
+    match loader.rewritten_from(id) {
+        Some(original) => println!(
+            "Repository: {} (rewritten from {original})",
+            template.repository
+        ),
+        None => println!("Repository: {}", template.repository),
+    }
+    if let Some(git_ref) = &template.git_ref {
+        println!("Ref: {git_ref}");
+    }
+    if let Some(subdirectory) = &template.subdirectory {
+        println!("Subdirectory: {subdirectory}");
+    }
+    match (template.shallow, template.sparse) {
+        (true, true) => println!("Clone: shallow, sparse"),
+        (true, false) => println!("Clone: shallow"),
+        (false, true) => println!("Clone: sparse"),
+        (false, false) => {}
+    }
+    println!("Description: {}", template.description);
+
+    if !template.files_to_customize.is_empty() {
+        println!("Files to customize:");
+        for file in &template.files_to_customize {
+            for replacement in &file.replacements {
+                println!(
+                    "  - {}: {} -> {:?}",
+                    file.path, replacement.placeholder, replacement.value_type
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn list_cache(config: &Config) -> Result<()> {
+    let entries = cache::list_entries(&config.cache_directory()?).await?;
+    if entries.is_empty() {
+        println!("The template cache is empty");
+        return Ok(());
+    }
+
+    println!(
+        "{:<24} {:<12} {:<10} {:>10}  LAST FETCHED",
+        "TEMPLATE", "REF", "COMMIT", "SIZE"
+    );
+    for entry in entries {
+        let name = entry
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let metadata = entry.metadata.as_ref();
+        let template = metadata.map_or(name, |m| m.template.clone());
+        let git_ref = metadata.and_then(|m| m.git_ref.as_deref()).unwrap_or("-");
+        let commit = metadata
+            .and_then(|m| m.commit.as_deref())
+            .map_or("-", |commit| &commit[..commit.len().min(8)]);
+        let last_fetched = entry.last_fetched.map_or("-".to_string(), |time| {
+            time.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        });
+
+        println!(
+            "{template:<24} {git_ref:<12} {commit:<10} {:>10}  {last_fetched}",
+            cache::format_size(entry.size)
+        );
+    }
+
+    Ok(())
+}
+
+async fn clean_cache(
+    config: &Config,
+    template_id: Option<&str>,
+    whole_repository: bool,
+) -> Result<()> {
+    let cache_dir = config.cache_directory()?;
+    let mut entries = cache::list_entries(&cache_dir).await?;
+
+    if let Some(id) = template_id {
+        let loader = TemplateLoader::from_config(config).await?;
+        let template = loader.get_template(id)?;
+        let cache_path = loader.cache_path(template);
+
+        // Other templates may share the repository but not the entry
+        entries.retain(|entry| {
+            entry.path == cache_path
+                || (whole_repository
+                    && entry
+                        .metadata
+                        .as_ref()
+                        .is_some_and(|m| m.repository == template.repository))
+        });
+    } else {
+        let index_cache = cache_dir.join(index::INDEX_CACHE_DIR);
+        if index_cache.exists() {
+            tokio::fs::remove_dir_all(&index_cache).await?;
+        }
+    }
+
+    for entry in &entries {
+        cache::remove_entry(&entry.path).await?;
+    }
+
+    println!("✅ Removed {} cached template(s)", entries.len());
+    Ok(())
+}
+
+async fn prune_cache(config: &Config, older_than: chrono::Duration) -> Result<()> {
+    let cutoff = chrono::Utc::now() - older_than;
+    let mut removed = 0;
+
+    for entry in cache::list_entries(&config.cache_directory()?).await? {
+        if entry.last_fetched.is_some_and(|time| time < cutoff) {
+            cache::remove_entry(&entry.path).await?;
+            removed += 1;
+        }
+    }
+
+    println!("✅ Removed {removed} cached template(s)");
     Ok(())
 }
 
@@ -89,14 +378,14 @@ mod tests {
 
     #[tokio::test]
     async fn test_list_templates() {
-        let result = list_templates().await;
+        let result = list_templates(&Config::default()).await;
         // Should not panic or error for basic functionality
         assert!(result.is_ok() || result.is_err()); // Either case is acceptable for testing
     }
 
     #[tokio::test]
     async fn test_update_templates() {
-        let result = update_templates().await;
+        let result = update_templates(&Config::default()).await;
         // Should not panic or error for basic functionality
         assert!(result.is_ok() || result.is_err()); // Either case is acceptable for testing
     }
@@ -155,6 +444,34 @@ mod tests {
             .success();
     }
 
+    #[test]
+    fn test_cli_list_with_registry_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry_path = temp_dir.path().join("registry.toml");
+        std::fs::write(
+            &registry_path,
+            r#"
+[[templates]]
+id = "team-rust"
+name = "Team Rust"
+language = "rust"
+repository = "https://example.com/team/rust-template"
+description = "Team Rust template"
+"#,
+        )
+        .unwrap();
+
+        let mut cmd = Command::cargo_bin("claudeforge").unwrap();
+        cmd.env("XDG_CACHE_HOME", temp_dir.path())
+            .arg("list")
+            .arg("--registry")
+            .arg(&registry_path)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("team-rust"))
+            .stdout(predicate::str::contains("go-claude-code"));
+    }
+
     #[test]
     fn test_cli_invalid_command() {
         let mut cmd = Command::cargo_bin("claudeforge").unwrap();
@@ -0,0 +1,223 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+use crate::template::index::INDEX_CACHE_DIR;
+use crate::template::Template;
+use crate::utils::fs as utils_fs;
+use crate::utils::lock::FileLock;
+
+/// Bookkeeping stored next to each cached template checkout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheMetadata {
+    /// Id of the template that last fetched this entry
+    pub template: String,
+    pub repository: String,
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    /// Checked out commit, for git templates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    pub last_fetched: DateTime<Utc>,
+}
+
+impl CacheMetadata {
+    /// Metadata for a template fetched just now
+    pub fn fetched_now(template: &Template) -> Self {
+        Self {
+            template: template.id.clone(),
+            repository: template.repository.clone(),
+            git_ref: template.git_ref.clone(),
+            commit: None,
+            last_fetched: Utc::now(),
+        }
+    }
+
+    /// Whether the entry was fetched longer than `max_age` ago
+    pub fn is_older_than(&self, max_age: chrono::Duration) -> bool {
+        Utc::now() - self.last_fetched > max_age
+    }
+}
+
+/// Metadata file of a cache entry, a sibling of the checkout so it is never
+/// copied into generated projects
+pub fn metadata_path(entry: &Path) -> PathBuf {
+    let mut file_name = entry.file_name().map(OsString::from).unwrap_or_default();
+    file_name.push(".json");
+    entry.with_file_name(file_name)
+}
+
+/// Lock file guarding a cache entry against concurrent claudeforge processes.
+/// Lock files are never deleted, so every process locks the same file.
+pub fn lock_path(entry: &Path) -> PathBuf {
+    let mut file_name = entry.file_name().map(OsString::from).unwrap_or_default();
+    file_name.push(".lock");
+    entry.with_file_name(file_name)
+}
+
+/// Read the metadata of a cache entry, if it has valid metadata
+pub async fn read_metadata(entry: &Path) -> Option<CacheMetadata> {
+    let content = fs::read_to_string(metadata_path(entry)).await.ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub async fn write_metadata(entry: &Path, metadata: &CacheMetadata) -> Result<()> {
+    let content = serde_json::to_string_pretty(metadata)?;
+    fs::write(metadata_path(entry), content).await?;
+    Ok(())
+}
+
+/// A template checkout in the cache directory
+#[derive(Debug)]
+pub struct CacheEntry {
+    pub path: PathBuf,
+    pub metadata: Option<CacheMetadata>,
+    /// Size on disk in bytes
+    pub size: u64,
+    /// From the metadata, or the directory's modification time for entries
+    /// cached before metadata was recorded
+    pub last_fetched: Option<DateTime<Utc>>,
+}
+
+/// List the template checkouts in the cache directory, sorted by name
+pub async fn list_entries(cache_dir: &Path) -> Result<Vec<CacheEntry>> {
+    let mut entries = Vec::new();
+    if !cache_dir.is_dir() {
+        return Ok(entries);
+    }
+
+    let mut dir = fs::read_dir(cache_dir).await?;
+    while let Some(entry) = dir.next_entry().await? {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        // Staging and backup directories are hidden, indexes are not templates
+        if name.starts_with('.') || name == INDEX_CACHE_DIR || !entry.file_type().await?.is_dir() {
+            continue;
+        }
+
+        let path = entry.path();
+        let metadata = read_metadata(&path).await;
+        let last_fetched = match &metadata {
+            Some(metadata) => Some(metadata.last_fetched),
+            None => entry
+                .metadata()
+                .await
+                .and_then(|m| m.modified())
+                .ok()
+                .map(DateTime::<Utc>::from),
+        };
+        let size = utils_fs::dir_size(&path).await?;
+
+        entries.push(CacheEntry {
+            path,
+            metadata,
+            size,
+            last_fetched,
+        });
+    }
+
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(entries)
+}
+
+/// Delete a cache entry together with its metadata
+pub async fn remove_entry(entry: &Path) -> Result<()> {
+    let _lock = FileLock::exclusive(&lock_path(entry)).await?;
+    utils_fs::remove_dir_all_robust(entry).await?;
+    let metadata = metadata_path(entry);
+    if metadata.exists() {
+        fs::remove_file(metadata).await?;
+    }
+    Ok(())
+}
+
+/// Human readable size, e.g. `1.5 MiB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_metadata_path_keeps_dots_in_entry_name() {
+        let path = metadata_path(Path::new("/cache/rust-1234abcd@v1.2.0"));
+        assert_eq!(path, Path::new("/cache/rust-1234abcd@v1.2.0.json"));
+    }
+
+    #[tokio::test]
+    async fn test_metadata_roundtrip_and_age() {
+        let temp_dir = TempDir::new().unwrap();
+        let entry = temp_dir.path().join("entry");
+        assert!(read_metadata(&entry).await.is_none());
+
+        let mut metadata = CacheMetadata {
+            template: "rust".to_string(),
+            repository: "https://example.com/rust.git".to_string(),
+            git_ref: Some("v1".to_string()),
+            commit: None,
+            last_fetched: Utc::now(),
+        };
+        write_metadata(&entry, &metadata).await.unwrap();
+        let read = read_metadata(&entry).await.unwrap();
+        assert_eq!(read.git_ref.as_deref(), Some("v1"));
+        assert!(!read.is_older_than(chrono::Duration::days(1)));
+
+        metadata.last_fetched = Utc::now() - chrono::Duration::days(8);
+        assert!(metadata.is_older_than(chrono::Duration::days(7)));
+    }
+
+    #[tokio::test]
+    async fn test_list_and_remove_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = temp_dir.path();
+
+        let entry = cache_dir.join("rust-1234abcd");
+        std::fs::create_dir_all(&entry).unwrap();
+        std::fs::write(entry.join("README.md"), "12345").unwrap();
+        let template = crate::template::registry::load_template_registry().unwrap()["rust"].clone();
+        write_metadata(&entry, &CacheMetadata::fetched_now(&template))
+            .await
+            .unwrap();
+
+        let legacy = cache_dir.join("go-1234abcd");
+        std::fs::create_dir_all(&legacy).unwrap();
+        std::fs::create_dir_all(cache_dir.join(INDEX_CACHE_DIR)).unwrap();
+        std::fs::create_dir_all(cache_dir.join(".staging-abc")).unwrap();
+
+        let entries = list_entries(cache_dir).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].path, legacy);
+        assert!(entries[0].metadata.is_none());
+        assert!(entries[0].last_fetched.is_some());
+        assert_eq!(entries[1].metadata.as_ref().unwrap().template, "rust");
+        assert_eq!(entries[1].size, 5);
+
+        remove_entry(&entry).await.unwrap();
+        assert!(!entry.exists());
+        assert!(!metadata_path(&entry).exists());
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MiB");
+    }
+}
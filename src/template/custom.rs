@@ -0,0 +1,210 @@
+use anyhow::Result;
+
+use crate::config::Config;
+use crate::error::ClaudeForgeError;
+use crate::git;
+use crate::template::source::{ArchiveLocation, TemplateSource};
+use crate::template::{FileCustomization, Replacement, Template, ValueType};
+
+/// Parse `path:placeholder:value_type` specs, grouping replacements by file
+pub fn parse_file_customizations(specs: &[String]) -> Result<Vec<FileCustomization>> {
+    let mut customizations: Vec<FileCustomization> = Vec::new();
+
+    for spec in specs {
+        let mut parts = spec.splitn(3, ':');
+        let (Some(path), Some(placeholder), Some(value_type)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(ClaudeForgeError::InvalidFileCustomization(format!(
+                "'{spec}' (expected path:placeholder:value_type)"
+            ))
+            .into());
+        };
+
+        if path.is_empty() || placeholder.is_empty() {
+            return Err(ClaudeForgeError::InvalidFileCustomization(format!(
+                "'{spec}' (path and placeholder must not be empty)"
+            ))
+            .into());
+        }
+
+        let value_type: ValueType = value_type
+            .parse()
+            .map_err(|e| ClaudeForgeError::InvalidFileCustomization(format!("'{spec}': {e}")))?;
+
+        let replacement = Replacement {
+            placeholder: placeholder.to_string(),
+            value_type,
+        };
+
+        match customizations.iter_mut().find(|c| c.path == path) {
+            Some(existing) => existing.replacements.push(replacement),
+            None => customizations.push(FileCustomization {
+                path: path.to_string(),
+                replacements: vec![replacement],
+                render: false,
+            }),
+        }
+    }
+
+    Ok(customizations)
+}
+
+/// Validate a custom template before it is stored in the configuration. The
+/// repository is checked after the config's URL rewrites, as `new` fetches it.
+pub async fn validate_custom_template(template: &Template, config: &Config) -> Result<()> {
+    if template.id.trim().is_empty() {
+        return Err(
+            ClaudeForgeError::TemplateValidation("template id must not be empty".into()).into(),
+        );
+    }
+
+    if template.id.contains(['/', '\\']) || template.id.starts_with('.') {
+        return Err(ClaudeForgeError::TemplateValidation(format!(
+            "template id '{}' must be a plain name such as 'rust-cli'",
+            template.id
+        ))
+        .into());
+    }
+
+    if template.repository.trim().is_empty() {
+        return Err(ClaudeForgeError::TemplateValidation(
+            "repository URL must not be empty".into(),
+        )
+        .into());
+    }
+
+    template.subdirectory_path()?;
+
+    let mut rewritten = template.clone();
+    if let Some(url) = config.templates.rewrite_url(&template.repository) {
+        rewritten.repository = url;
+    }
+
+    match rewritten.source() {
+        TemplateSource::Local(path) => {
+            if !path.is_dir() {
+                return Err(ClaudeForgeError::TemplateValidation(format!(
+                    "local template directory {path:?} does not exist"
+                ))
+                .into());
+            }
+        }
+        TemplateSource::Archive {
+            location: ArchiveLocation::Path(path),
+            ..
+        } => {
+            if !path.is_file() {
+                return Err(ClaudeForgeError::TemplateValidation(format!(
+                    "template archive {path:?} does not exist"
+                ))
+                .into());
+            }
+        }
+        TemplateSource::Archive {
+            location: ArchiveLocation::Url(url),
+            ..
+        } => {
+            reqwest::Client::new()
+                .head(&url)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|e| {
+                    ClaudeForgeError::TemplateValidation(format!(
+                        "cannot access template archive {url}: {e}"
+                    ))
+                })?;
+        }
+        TemplateSource::Git(url) => {
+            git::backend(config.git.backend)
+                .check_remote_access(&url, &git::Auth::from_config(&config.git))
+                .map_err(|e| {
+                    ClaudeForgeError::TemplateValidation(format!(
+                        "cannot access repository {url}: {e:#}"
+                    ))
+                })?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::UrlRewrite;
+    use crate::utils::testing::local_template;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_file_customizations_groups_by_path() {
+        let specs = vec![
+            "pyproject.toml:my-project:ProjectName".to_string(),
+            "pyproject.toml:Author:AuthorName".to_string(),
+            "go.mod:github.com/x/y:Custom=github.com/team/svc".to_string(),
+        ];
+
+        let customizations = parse_file_customizations(&specs).unwrap();
+
+        assert_eq!(customizations.len(), 2);
+        assert_eq!(customizations[0].path, "pyproject.toml");
+        assert_eq!(customizations[0].replacements.len(), 2);
+        assert!(matches!(
+            &customizations[1].replacements[0].value_type,
+            ValueType::Custom(value) if value == "github.com/team/svc"
+        ));
+    }
+
+    #[test]
+    fn test_parse_file_customizations_invalid() {
+        for spec in [
+            "README.md",
+            "README.md:name",
+            ":name:ProjectName",
+            "a:b:Unknown",
+        ] {
+            let err = parse_file_customizations(&[spec.to_string()]).unwrap_err();
+            assert!(
+                matches!(
+                    err.downcast_ref::<ClaudeForgeError>(),
+                    Some(ClaudeForgeError::InvalidFileCustomization(_))
+                ),
+                "spec {spec} should be rejected"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_custom_template() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let mut template = local_template(format!("file://{}", temp_dir.path().display()));
+        assert!(validate_custom_template(&template, &Config::default())
+            .await
+            .is_ok());
+
+        template.repository = temp_dir
+            .path()
+            .join("missing")
+            .to_string_lossy()
+            .to_string();
+        let err = validate_custom_template(&template, &Config::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ClaudeForgeError>(),
+            Some(ClaudeForgeError::TemplateValidation(_))
+        ));
+
+        // The repository is checked where `new` would fetch it from
+        let mut config = Config::default();
+        config.templates.url_rewrite.push(UrlRewrite {
+            prefix: "https://github.com/".to_string(),
+            replacement: format!("{}/", temp_dir.path().display()),
+        });
+        std::fs::create_dir_all(temp_dir.path().join("team")).unwrap();
+        template.repository = "https://github.com/team".to_string();
+        assert!(validate_custom_template(&template, &config).await.is_ok());
+    }
+}
@@ -0,0 +1,255 @@
+use minijinja::syntax::SyntaxConfig;
+use minijinja::value::Serde;
+use minijinja::{AutoEscape, Environment, ErrorKind, UndefinedBehavior, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::error::ClaudeForgeError;
+
+/// Values available to templated files, by variable name
+pub type Variables = BTreeMap<String, serde_json::Value>;
+
+/// Renders templated files with Jinja syntax: `{{ var }}` expressions,
+/// `{% if %}` and `{% for %}` blocks and `{{ var | filter }}` filters
+pub struct TemplateEngine {
+    env: Environment<'static>,
+}
+
+impl Default for TemplateEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TemplateEngine {
+    pub fn new() -> Self {
+        let mut env = Environment::new();
+
+        // Printing an unknown variable is an error, testing it in `if` is not
+        env.set_undefined_behavior(UndefinedBehavior::SemiStrict);
+        // Project files are not HTML, whatever their extension
+        env.set_auto_escape_callback(|_| AutoEscape::None);
+        env.set_debug(true);
+        env.set_syntax(
+            SyntaxConfig::builder()
+                .trim_blocks(true)
+                .lstrip_blocks(true)
+                .keep_trailing_newline(true)
+                .build()
+                .expect("default delimiters are valid"),
+        );
+
+        env.add_filter("snake_case", |value: &str| {
+            join_words(value, "_", Case::Lower)
+        });
+        env.add_filter("kebab_case", |value: &str| {
+            join_words(value, "-", Case::Lower)
+        });
+        env.add_filter("camel_case", |value: &str| {
+            join_words(value, "", Case::Camel)
+        });
+        env.add_filter("pascal_case", |value: &str| {
+            join_words(value, "", Case::Pascal)
+        });
+        env.add_filter("uppercase", |value: &str| value.to_uppercase());
+        env.add_filter("lowercase", |value: &str| value.to_lowercase());
+
+        Self { env }
+    }
+
+    /// Render the contents of the file at `path`, which is only used to
+    /// report errors
+    pub fn render(
+        &self,
+        path: &Path,
+        source: &str,
+        variables: &Variables,
+    ) -> Result<String, ClaudeForgeError> {
+        let name = path.to_string_lossy();
+        self.env
+            .render_named_str(&name, source, Value::from(Serde(variables)))
+            .map_err(|e| render_error(path, source, e))
+    }
+}
+
+fn render_error(path: &Path, source: &str, error: minijinja::Error) -> ClaudeForgeError {
+    let message = match error.detail() {
+        Some(detail) => format!("{}: {}", error.kind(), detail),
+        None => error.kind().to_string(),
+    };
+    let location = error
+        .range()
+        .map(|range| location(source, range.start))
+        .or_else(|| error.line().map(|line| (line, 1)));
+
+    match (error.kind(), location) {
+        (ErrorKind::SyntaxError, Some((line, column))) => ClaudeForgeError::TemplateSyntax {
+            path: path.to_path_buf(),
+            line,
+            column,
+            message,
+        },
+        (_, Some((line, column))) => ClaudeForgeError::TemplateRender {
+            path: path.to_path_buf(),
+            message: format!("{message} at line {line}, column {column}"),
+        },
+        (_, None) => ClaudeForgeError::TemplateRender {
+            path: path.to_path_buf(),
+            message,
+        },
+    }
+}
+
+/// One-based line and column of a byte offset
+fn location(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+    (line, column)
+}
+
+enum Case {
+    Lower,
+    Camel,
+    Pascal,
+}
+
+/// Split `value` into words at separators and lower-to-upper case changes,
+/// then join them in the given case
+fn join_words(value: &str, separator: &str, case: Case) -> String {
+    let mut words: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut previous_lower = false;
+
+    for c in value.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            previous_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && previous_lower {
+            words.push(std::mem::take(&mut current));
+        }
+        previous_lower = c.is_lowercase() || c.is_numeric();
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            let lower = word.to_lowercase();
+            match case {
+                Case::Camel if i == 0 => lower,
+                Case::Lower => lower,
+                Case::Camel | Case::Pascal => capitalize(&lower),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn variables() -> Variables {
+        let mut variables = Variables::new();
+        variables.insert("PROJECT_NAME".to_string(), json!("myWeb-service"));
+        variables.insert("database".to_string(), json!("postgres"));
+        variables.insert("features".to_string(), json!(["auth", "metrics"]));
+        variables
+    }
+
+    #[test]
+    fn test_render_expressions_blocks_and_filters() {
+        let engine = TemplateEngine::new();
+        let source = "\
+name = \"{{ PROJECT_NAME | snake_case }}\"
+{% if database == \"postgres\" %}
+sqlx = \"0.8\"
+{% endif %}
+{% for feature in features %}
+- {{ feature | upper }}
+{% endfor %}
+";
+        let rendered = engine
+            .render(Path::new("Cargo.toml"), source, &variables())
+            .unwrap();
+
+        assert_eq!(
+            rendered,
+            "name = \"my_web_service\"\nsqlx = \"0.8\"\n- AUTH\n- METRICS\n"
+        );
+    }
+
+    #[test]
+    fn test_case_filters() {
+        let engine = TemplateEngine::new();
+        let source = "{{ PROJECT_NAME | kebab_case }} {{ PROJECT_NAME | camel_case }} \
+                      {{ PROJECT_NAME | pascal_case }} {{ database | uppercase }}";
+        let rendered = engine
+            .render(Path::new("README.md"), source, &variables())
+            .unwrap();
+
+        assert_eq!(
+            rendered,
+            "my-web-service myWebService MyWebService POSTGRES"
+        );
+    }
+
+    #[test]
+    fn test_does_not_escape_html() {
+        let engine = TemplateEngine::new();
+        let mut variables = Variables::new();
+        variables.insert("title".to_string(), json!("<Tom & Jerry>"));
+        let rendered = engine
+            .render(Path::new("index.html"), "{{ title }}", &variables)
+            .unwrap();
+
+        assert_eq!(rendered, "<Tom & Jerry>");
+    }
+
+    #[test]
+    fn test_syntax_error_reports_line_and_column() {
+        let engine = TemplateEngine::new();
+        let source = "name = \"{{ PROJECT_NAME }}\"\nversion = {{ version }\n";
+        let err = engine
+            .render(Path::new("Cargo.toml"), source, &variables())
+            .unwrap_err();
+
+        match err {
+            ClaudeForgeError::TemplateSyntax { line, column, .. } => {
+                assert_eq!(line, 2);
+                assert!(column > 1);
+            }
+            other => panic!("expected a syntax error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_undefined_variable_is_an_error() {
+        let engine = TemplateEngine::new();
+        let source = "{% if missing %}never{% endif %}{{ missing }}";
+        let err = engine
+            .render(Path::new("README.md"), source, &variables())
+            .unwrap_err();
+
+        assert!(matches!(err, ClaudeForgeError::TemplateRender { .. }));
+        assert!(err.to_string().contains("line 1"));
+    }
+}
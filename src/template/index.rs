@@ -0,0 +1,216 @@
+use anyhow::Result;
+use reqwest::header::{CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::fs;
+use tracing::{debug, warn};
+
+use crate::error::ClaudeForgeError;
+use crate::template::{registry, Template};
+use crate::utils::fs as utils_fs;
+
+/// Directory inside the template cache holding cached index responses
+pub const INDEX_CACHE_DIR: &str = "indexes";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Last successful response of a remote index, with its cache validators
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedIndex {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    is_json: bool,
+    body: String,
+}
+
+impl CachedIndex {
+    fn templates(&self) -> Result<Vec<Template>> {
+        parse_index(&self.url, &self.body, self.is_json)
+    }
+}
+
+/// Location of the cached copy of a remote index
+pub fn index_cache_path(cache_dir: &Path, url: &str) -> PathBuf {
+    cache_dir
+        .join(INDEX_CACHE_DIR)
+        .join(format!("{}.json", utils_fs::cache_key(url)))
+}
+
+/// Load the templates of a remote index. The cached copy is revalidated with
+/// `If-None-Match`/`If-Modified-Since` and used as-is when the server answers
+/// 304 or cannot be reached.
+pub async fn load_index(url: &str, cache_dir: &Path) -> Result<Vec<Template>> {
+    let cache_path = index_cache_path(cache_dir, url);
+    let cached = read_cached_index(&cache_path).await;
+
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(ClaudeForgeError::from)?;
+    let mut request = client.get(url);
+    if let Some(cached) = &cached {
+        if let Some(etag) = &cached.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let response = match request.send().await.and_then(|r| r.error_for_status()) {
+        Ok(response) => response,
+        Err(e) => {
+            let Some(cached) = cached else {
+                return Err(ClaudeForgeError::NetworkError(e).into());
+            };
+            warn!("Could not refresh template index {url} ({e}), using cached copy");
+            return cached.templates();
+        }
+    };
+
+    if response.status() == StatusCode::NOT_MODIFIED {
+        let Some(cached) = cached else {
+            return Err(index_error(
+                url,
+                "server answered 304 but nothing is cached",
+            ));
+        };
+        debug!("Template index {} is unchanged", url);
+        return cached.templates();
+    }
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    let etag = header(ETAG);
+    let last_modified = header(LAST_MODIFIED);
+    let is_json = url.to_ascii_lowercase().ends_with(".json")
+        || header(CONTENT_TYPE).is_some_and(|content_type| content_type.contains("json"));
+    let body = response.text().await.map_err(ClaudeForgeError::from)?;
+
+    let fetched = CachedIndex {
+        url: url.to_string(),
+        etag,
+        last_modified,
+        is_json,
+        body,
+    };
+    // Only a valid index replaces the cached copy
+    let templates = fetched.templates()?;
+
+    if let Some(parent) = cache_path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    fs::write(&cache_path, serde_json::to_string_pretty(&fetched)?).await?;
+    debug!("Cached template index {} at {:?}", url, cache_path);
+
+    Ok(templates)
+}
+
+/// Load the templates of a remote index from its cached copy only, without
+/// touching the network
+pub async fn load_cached_index(url: &str, cache_dir: &Path) -> Result<Vec<Template>> {
+    match read_cached_index(&index_cache_path(cache_dir, url)).await {
+        Some(cached) => cached.templates(),
+        None => Err(index_error(url, "not cached, it is fetched when online")),
+    }
+}
+
+async fn read_cached_index(path: &Path) -> Option<CachedIndex> {
+    let content = fs::read_to_string(path).await.ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn parse_index(url: &str, body: &str, is_json: bool) -> Result<Vec<Template>> {
+    registry::parse_registry(body, is_json).map_err(|message| index_error(url, &message))
+}
+
+fn index_error(url: &str, message: &str) -> anyhow::Error {
+    ClaudeForgeError::IndexError {
+        url: url.to_string(),
+        message: message.to_string(),
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing::{http_response, serve_http};
+    use tempfile::TempDir;
+
+    const INDEX: &str = r#"
+[[templates]]
+id = "team-rust"
+name = "Team Rust"
+language = "rust"
+repository = "https://example.com/team/rust-template"
+description = "Team Rust template"
+"#;
+
+    fn ok_response(body: &str) -> Vec<u8> {
+        http_response(
+            "200 OK",
+            &[
+                ("ETag", "\"v1\""),
+                ("Last-Modified", "Wed, 01 Jan 2025 00:00:00 GMT"),
+            ],
+            body.as_bytes(),
+        )
+    }
+
+    fn not_modified_response() -> Vec<u8> {
+        http_response("304 Not Modified", &[], b"")
+    }
+
+    #[tokio::test]
+    async fn test_load_index_revalidates_cached_copy() {
+        let temp_dir = TempDir::new().unwrap();
+        let (url, requests) = serve_http(
+            vec![ok_response(INDEX), not_modified_response()],
+            "index.toml",
+        );
+
+        let templates = load_index(&url, temp_dir.path()).await.unwrap();
+        assert_eq!(templates[0].id, "team-rust");
+        assert!(!requests.recv().unwrap().contains("if-none-match"));
+        assert!(index_cache_path(temp_dir.path(), &url).exists());
+
+        let templates = load_index(&url, temp_dir.path()).await.unwrap();
+        assert_eq!(templates[0].id, "team-rust");
+        let request = requests.recv().unwrap();
+        assert!(request.contains("if-none-match: \"v1\""));
+        assert!(request.contains("if-modified-since: wed, 01 jan 2025"));
+    }
+
+    #[tokio::test]
+    async fn test_load_index_falls_back_to_cache_when_unreachable() {
+        let temp_dir = TempDir::new().unwrap();
+        let (url, _requests) = serve_http(vec![ok_response(INDEX)], "index.toml");
+        load_index(&url, temp_dir.path()).await.unwrap();
+
+        // The server is gone after its single response
+        let templates = load_index(&url, temp_dir.path()).await.unwrap();
+        assert_eq!(templates[0].id, "team-rust");
+    }
+
+    #[tokio::test]
+    async fn test_load_index_invalid_body_keeps_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let (url, _requests) = serve_http(vec![ok_response("not = [valid")], "index.toml");
+
+        let err = load_index(&url, temp_dir.path()).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ClaudeForgeError>(),
+            Some(ClaudeForgeError::IndexError { .. })
+        ));
+        assert!(!index_cache_path(temp_dir.path(), &url).exists());
+    }
+}
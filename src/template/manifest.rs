@@ -0,0 +1,133 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::path::Path;
+use tokio::fs;
+use tracing::debug;
+
+use crate::error::ClaudeForgeError;
+use crate::template::{FileCustomization, Template, TemplateVariable};
+
+/// Name of the manifest a template repository may carry at its root
+pub const MANIFEST_FILE: &str = ".claudeforge.toml";
+
+/// Template metadata maintained alongside the template files. Anything set
+/// here takes precedence over the template's registry entry.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateManifest {
+    pub description: Option<String>,
+    pub files: Option<Vec<FileCustomization>>,
+    pub variables: Option<Vec<TemplateVariable>>,
+}
+
+impl TemplateManifest {
+    /// Override the registry entry of a template with the manifest's settings
+    pub fn apply_to(self, template: &mut Template) {
+        if let Some(description) = self.description {
+            template.description = description;
+        }
+        if let Some(files) = self.files {
+            template.files_to_customize = files;
+        }
+        if let Some(variables) = self.variables {
+            template.variables = variables;
+        }
+    }
+}
+
+/// Read the manifest of a fetched template, if it has one
+pub async fn load_manifest(template_root: &Path) -> Result<Option<TemplateManifest>> {
+    let path = template_root.join(MANIFEST_FILE);
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    let manifest_error = |message: String| ClaudeForgeError::InvalidManifest {
+        path: path.clone(),
+        message,
+    };
+
+    let content = fs::read_to_string(&path)
+        .await
+        .map_err(|e| manifest_error(e.to_string()))?;
+    let manifest = toml::from_str(&content).map_err(|e| manifest_error(e.to_string()))?;
+
+    debug!("Loaded template manifest {:?}", path);
+    Ok(Some(manifest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::{registry, ValueType};
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_load_manifest_overrides_registry_entry() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join(MANIFEST_FILE),
+            r#"
+description = "Described by the template itself"
+
+[[files]]
+path = "pyproject.toml"
+
+[[files.replacements]]
+placeholder = "my-app"
+value_type = "ProjectName"
+
+[[variables]]
+name = "license"
+description = "License of the generated project"
+default = "MIT"
+"#,
+        )
+        .unwrap();
+
+        let mut template = registry::load_template_registry().unwrap()["rust"].clone();
+        let manifest = load_manifest(temp_dir.path()).await.unwrap().unwrap();
+        manifest.apply_to(&mut template);
+
+        assert_eq!(template.description, "Described by the template itself");
+        assert_eq!(template.files_to_customize.len(), 1);
+        assert_eq!(template.files_to_customize[0].path, "pyproject.toml");
+        assert!(matches!(
+            template.files_to_customize[0].replacements[0].value_type,
+            ValueType::ProjectName
+        ));
+        assert_eq!(template.variables[0].name, "license");
+        assert_eq!(template.variables[0].default.as_deref(), Some("MIT"));
+    }
+
+    #[tokio::test]
+    async fn test_load_manifest_partial_keeps_registry_values() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join(MANIFEST_FILE),
+            "description = \"Only the description\"\n",
+        )
+        .unwrap();
+
+        let mut template = registry::load_template_registry().unwrap()["rust"].clone();
+        let registry_files = template.files_to_customize.len();
+        let manifest = load_manifest(temp_dir.path()).await.unwrap().unwrap();
+        manifest.apply_to(&mut template);
+
+        assert_eq!(template.description, "Only the description");
+        assert_eq!(template.files_to_customize.len(), registry_files);
+    }
+
+    #[tokio::test]
+    async fn test_load_manifest_missing_or_invalid() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(load_manifest(temp_dir.path()).await.unwrap().is_none());
+
+        std::fs::write(temp_dir.path().join(MANIFEST_FILE), "files = 1\n").unwrap();
+        let err = load_manifest(temp_dir.path()).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ClaudeForgeError>(),
+            Some(ClaudeForgeError::InvalidManifest { .. })
+        ));
+    }
+}
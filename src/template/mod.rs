@@ -1,25 +1,139 @@
+pub mod cache;
+pub mod custom;
+pub mod engine;
+pub mod index;
 pub mod loader;
+pub mod manifest;
 pub mod processor;
+pub mod prompt;
 pub mod registry;
+pub mod source;
+pub mod vars;
 
-use crate::cli::Language;
 use serde::{Deserialize, Serialize};
+use std::path::{Component, Path};
+
+use self::source::TemplateSource;
+use crate::error::ClaudeForgeError;
 
 /// Template configuration
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct Template {
+    /// Unique identifier used on the command line, e.g. `rust` or `go-grpc`
+    pub id: String,
     pub name: String,
-    pub language: Language,
+    pub language: String,
     pub repository: String,
+    /// Branch, tag or commit SHA to check out instead of the default branch
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    /// Path of the template inside its repository, for monorepos
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subdirectory: Option<String>,
     pub description: String,
+    #[serde(default)]
     pub files_to_customize: Vec<FileCustomization>,
+    /// Variables available to the template as `{{name}}` placeholders
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<TemplateVariable>,
+    /// Fetch only the latest commit of git templates
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub shallow: bool,
+    /// Check out only the template's subdirectory of a git repository
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sparse: bool,
+}
+
+impl Template {
+    /// Where the template files are fetched from
+    pub fn source(&self) -> TemplateSource {
+        TemplateSource::parse(&self.repository)
+    }
+
+    /// Relative path of the template inside its repository, rejecting paths
+    /// that would escape the checkout
+    pub fn subdirectory_path(&self) -> Result<Option<&Path>, ClaudeForgeError> {
+        let Some(subdirectory) = &self.subdirectory else {
+            return Ok(None);
+        };
+
+        let path = Path::new(subdirectory);
+        let escapes = path
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+        if escapes {
+            return Err(ClaudeForgeError::TemplateValidation(format!(
+                "subdirectory '{subdirectory}' of template {} must be a relative path inside the repository",
+                self.id
+            )));
+        }
+
+        Ok(Some(path))
+    }
 }
 
 /// File customization rules
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FileCustomization {
     pub path: String,
+    /// Literal placeholders, replaced before the file is rendered
+    #[serde(default)]
     pub replacements: Vec<Replacement>,
+    /// Render the file as a Jinja template instead of only replacing
+    /// `{{name}}` placeholders
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub render: bool,
+}
+
+/// Variable declared by a template
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TemplateVariable {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    /// Allowed values, offered when prompting for the variable
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<String>,
+    #[serde(
+        rename = "type",
+        default,
+        skip_serializing_if = "VariableType::is_string"
+    )]
+    pub var_type: VariableType,
+    /// Regular expression the whole value, or each list item, must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Smallest integer, or shortest string or list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<i64>,
+    /// Largest integer, or longest string or list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<i64>,
+    /// Fail when neither a value nor a default is given
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub required: bool,
+}
+
+/// Type of a template variable's value
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VariableType {
+    #[default]
+    String,
+    Bool,
+    Integer,
+    /// One of the variable's choices
+    Choice,
+    /// Strings, given as a comma separated list on the command line
+    List,
+}
+
+impl VariableType {
+    fn is_string(&self) -> bool {
+        *self == VariableType::String
+    }
 }
 
 /// Text replacement rule
@@ -38,3 +152,25 @@ pub enum ValueType {
     CurrentDate,
     Custom(String),
 }
+
+impl std::str::FromStr for ValueType {
+    type Err = String;
+
+    /// Parse a value type name, with `Custom=<value>` for literal values
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ProjectName" => Ok(ValueType::ProjectName),
+            "ProjectPath" => Ok(ValueType::ProjectPath),
+            "AuthorName" => Ok(ValueType::AuthorName),
+            "AuthorEmail" => Ok(ValueType::AuthorEmail),
+            "CurrentDate" => Ok(ValueType::CurrentDate),
+            other => match other.strip_prefix("Custom=") {
+                Some(value) => Ok(ValueType::Custom(value.to_string())),
+                None => Err(format!(
+                    "unknown value type '{other}' (expected ProjectName, ProjectPath, \
+                     AuthorName, AuthorEmail, CurrentDate or Custom=<value>)"
+                )),
+            },
+        }
+    }
+}
@@ -1,25 +1,57 @@
 use anyhow::{Context, Result};
-use std::collections::HashMap;
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
 use tokio::fs;
 use tracing::{debug, info};
 
-use crate::cli::Language;
 use crate::error::ClaudeForgeError;
 use crate::git;
+use crate::template::engine::{TemplateEngine, Variables};
+use crate::template::manifest::{self, MANIFEST_FILE};
+use crate::template::prompt;
+use crate::template::vars;
 use crate::template::{loader::TemplateLoader, Template, ValueType};
 use crate::utils::fs as fs_utils;
 
 pub async fn create_project(
-    language: Language,
+    template_id: &str,
     name: String,
     directory: Option<PathBuf>,
     skip_prompts: bool,
 ) -> Result<()> {
-    info!("Creating new {} project: {}", language, name);
-
     let loader = TemplateLoader::new().await?;
-    let template_path = loader.get_or_fetch(language.clone()).await?;
+    create_project_with_loader(
+        &loader,
+        template_id,
+        None,
+        name,
+        directory,
+        skip_prompts,
+        Variables::new(),
+    )
+    .await
+}
+
+/// Create a project using templates resolved by the given loader, optionally
+/// pinning the template to a branch, tag or commit. `variables` override the
+/// defaults of the template's variables and the built-in ones; the remaining
+/// variables are prompted for when stdin is a terminal and `skip_prompts` is
+/// not set.
+pub async fn create_project_with_loader(
+    loader: &TemplateLoader,
+    template_id: &str,
+    git_ref: Option<String>,
+    name: String,
+    directory: Option<PathBuf>,
+    skip_prompts: bool,
+    mut variables: Variables,
+) -> Result<()> {
+    info!("Creating new {} project: {}", template_id, name);
+
+    let mut template = loader.get_template(template_id)?.clone();
+    if git_ref.is_some() {
+        template.git_ref = git_ref;
+    }
 
     let target_dir = directory.unwrap_or_else(|| PathBuf::from(".")).join(&name);
 
@@ -32,14 +64,40 @@ pub async fn create_project(
         }
     }
 
-    // Copy template files
-    info!("Copying template files...");
-    copy_template(&template_path, &target_dir).await?;
+    // Copy the files aside so the cache is not locked while prompting
+    let staging = tempfile::Builder::new()
+        .prefix("claudeforge-")
+        .tempdir()
+        .context("Failed to create a staging directory")?;
+    {
+        let files = loader.get_or_fetch_locked(&template).await?;
+
+        // The template's own manifest is more up to date than its registry entry
+        if let Some(manifest) = manifest::load_manifest(&files.path).await? {
+            manifest.apply_to(&mut template);
+        }
+
+        info!("Copying template files...");
+        copy_template(&files.path, staging.path()).await?;
+    }
+
+    // Ask for the template's variables unless running unattended
+    if !skip_prompts && std::io::stdin().is_terminal() {
+        prompt::prompt_variables(
+            &template.variables,
+            &mut variables,
+            &mut std::io::stdin().lock(),
+            &mut std::io::stderr(),
+        )?;
+    }
+    vars::validate_variables(&template.variables, &mut variables)?;
+
+    copy_template(staging.path(), &target_dir).await?;
+    drop(staging);
 
     // Customize files
     info!("Customizing project files...");
-    let template = loader.get_template(language)?;
-    customize_project_files(&target_dir, &name, template).await?;
+    customize_project_files(&target_dir, &name, &template, variables).await?;
 
     // Initialize git repository
     info!("Initializing git repository...");
@@ -58,8 +116,8 @@ async fn copy_template(template_path: &Path, target_dir: &Path) -> Result<()> {
         .await
         .with_context(|| format!("Failed to create directory: {target_dir:?}"))?;
 
-    // Copy all files except .git directory
-    fs_utils::copy_dir_recursive(template_path, target_dir, Some(&[".git"])).await?;
+    // Copy all files except .git directory and the template manifest
+    fs_utils::copy_dir_recursive(template_path, target_dir, Some(&[".git", MANIFEST_FILE])).await?;
 
     Ok(())
 }
@@ -68,8 +126,18 @@ async fn customize_project_files(
     project_dir: &Path,
     project_name: &str,
     template: &Template,
+    overrides: Variables,
 ) -> Result<()> {
-    let replacements = build_replacements(project_name).await?;
+    let mut variables = build_variables(project_name).await?;
+
+    for variable in &template.variables {
+        if let Some(default) = &variable.default {
+            variables.insert(variable.name.clone(), default.clone().into());
+        }
+    }
+    variables.extend(overrides);
+
+    let engine = TemplateEngine::new();
 
     for customization in &template.files_to_customize {
         let file_path = project_dir.join(&customization.path);
@@ -81,8 +149,12 @@ async fn customize_project_files(
                 .await
                 .with_context(|| format!("Failed to read file: {file_path:?}"))?;
 
-            let new_content =
-                apply_replacements(&content, &replacements, &customization.replacements);
+            let replaced = apply_replacements(&content, &variables, &customization.replacements);
+            let new_content = if customization.render {
+                engine.render(Path::new(&customization.path), &replaced, &variables)?
+            } else {
+                apply_placeholders(&replaced, &variables)
+            };
 
             fs::write(&file_path, new_content)
                 .await
@@ -95,69 +167,62 @@ async fn customize_project_files(
     Ok(())
 }
 
-async fn build_replacements(project_name: &str) -> Result<HashMap<String, String>> {
-    let mut replacements = HashMap::new();
+/// Built-in variables available to every template
+async fn build_variables(project_name: &str) -> Result<Variables> {
+    let mut variables = Variables::new();
 
-    replacements.insert("{{PROJECT_NAME}}".to_string(), project_name.to_string());
-    replacements.insert(
-        "{{CURRENT_DATE}}".to_string(),
-        chrono::Local::now().format("%Y-%m-%d").to_string(),
+    variables.insert("PROJECT_NAME".to_string(), project_name.into());
+    variables.insert(
+        "CURRENT_DATE".to_string(),
+        chrono::Local::now().format("%Y-%m-%d").to_string().into(),
     );
 
-    // Get git config for author info
-    if let Ok(output) = tokio::process::Command::new("git")
-        .args(["config", "user.name"])
-        .output()
-        .await
-    {
-        if output.status.success() {
-            let author = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            replacements.insert("{{AUTHOR_NAME}}".to_string(), author);
-        }
-    }
-
-    if let Ok(output) = tokio::process::Command::new("git")
-        .args(["config", "user.email"])
-        .output()
-        .await
-    {
-        if output.status.success() {
-            let email = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            replacements.insert("{{AUTHOR_EMAIL}}".to_string(), email);
-        }
-    }
+    // Get git config for author info, empty when unknown so templates can
+    // test for it
+    let (author, email) = git::user_identity();
+    variables.insert("AUTHOR_NAME".to_string(), author.unwrap_or_default().into());
+    variables.insert("AUTHOR_EMAIL".to_string(), email.unwrap_or_default().into());
 
-    Ok(replacements)
+    Ok(variables)
 }
 
+/// Replace the literal placeholders of a file's customization rules
 fn apply_replacements(
     content: &str,
-    global_replacements: &HashMap<String, String>,
+    variables: &Variables,
     template_replacements: &[crate::template::Replacement],
 ) -> String {
     let mut result = content.to_string();
 
-    // Apply template-specific replacements
     for replacement in template_replacements {
+        let variable = |name: &str| variables.get(name).map(vars::value_to_string);
         let value = match &replacement.value_type {
-            ValueType::ProjectName => global_replacements.get("{{PROJECT_NAME}}"),
-            ValueType::AuthorName => global_replacements.get("{{AUTHOR_NAME}}"),
-            ValueType::AuthorEmail => global_replacements.get("{{AUTHOR_EMAIL}}"),
-            ValueType::CurrentDate => global_replacements.get("{{CURRENT_DATE}}"),
+            ValueType::ProjectName => variable("PROJECT_NAME"),
+            ValueType::AuthorName => variable("AUTHOR_NAME"),
+            ValueType::AuthorEmail => variable("AUTHOR_EMAIL"),
+            ValueType::CurrentDate => variable("CURRENT_DATE"),
             ValueType::ProjectPath => None, // TODO: Implement project path replacement
-            ValueType::Custom(custom_value) => Some(custom_value),
+            // The value of the variable with that name, or the text itself
+            ValueType::Custom(custom_value) => {
+                variable(custom_value).or_else(|| Some(custom_value.clone()))
+            }
         };
 
         if let Some(value) = value {
-            result = result.replace(&replacement.placeholder, value);
+            result = result.replace(&replacement.placeholder, &value);
         }
     }
 
-    // Apply global replacements
-    for (placeholder, value) in global_replacements {
-        result = result.replace(placeholder, value);
-    }
+    result
+}
 
+/// Replace `{{name}}` placeholders of files that are not rendered, leaving
+/// any other braces alone
+fn apply_placeholders(content: &str, variables: &Variables) -> String {
+    let mut result = content.to_string();
+    for (name, value) in variables {
+        result = result.replace(&format!("{{{{{name}}}}}"), &vars::value_to_string(value));
+    }
     result
 }
 
@@ -180,19 +245,21 @@ async fn initialize_git_repo(project_dir: &Path) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::testing::local_template;
     use tempfile::TempDir;
 
     #[tokio::test]
-    async fn test_build_replacements() {
-        let replacements = build_replacements("my-project").await.unwrap();
-        assert_eq!(replacements.get("{{PROJECT_NAME}}").unwrap(), "my-project");
-        assert!(replacements.contains_key("{{CURRENT_DATE}}"));
+    async fn test_build_variables() {
+        let variables = build_variables("my-project").await.unwrap();
+        assert_eq!(variables["PROJECT_NAME"], "my-project");
+        assert!(variables.contains_key("CURRENT_DATE"));
+        assert!(variables.contains_key("AUTHOR_NAME"));
     }
 
     #[test]
     fn test_apply_replacements() {
-        let mut global_replacements = HashMap::new();
-        global_replacements.insert("{{PROJECT_NAME}}".to_string(), "test-project".to_string());
+        let mut variables = Variables::new();
+        variables.insert("PROJECT_NAME".to_string(), "test-project".into());
 
         let template_replacements = vec![crate::template::Replacement {
             placeholder: "my-project".to_string(),
@@ -200,7 +267,7 @@ mod tests {
         }];
 
         let content = "This is my-project template";
-        let result = apply_replacements(content, &global_replacements, &template_replacements);
+        let result = apply_replacements(content, &variables, &template_replacements);
 
         assert_eq!(result, "This is test-project template");
     }
@@ -215,7 +282,7 @@ mod tests {
         fs::create_dir(&project_path).await.unwrap();
 
         let result = create_project(
-            Language::Rust,
+            "rust",
             project_name.to_string(),
             Some(temp_dir.path().to_path_buf()),
             false,
@@ -235,7 +302,7 @@ mod tests {
         fs::create_dir(&project_path).await.unwrap();
 
         let result = create_project(
-            Language::Rust,
+            "rust",
             project_name.to_string(),
             Some(temp_dir.path().to_path_buf()),
             true,
@@ -252,7 +319,7 @@ mod tests {
         let project_name = "test-project";
 
         let result = create_project(
-            Language::Rust,
+            "rust",
             project_name.to_string(),
             Some(temp_dir.path().to_path_buf()),
             true,
@@ -284,9 +351,9 @@ mod tests {
 
     #[test]
     fn test_apply_replacements_with_multiple_placeholders() {
-        let mut global_replacements = HashMap::new();
-        global_replacements.insert("{{PROJECT_NAME}}".to_string(), "test-project".to_string());
-        global_replacements.insert("{{AUTHOR_NAME}}".to_string(), "Test Author".to_string());
+        let mut variables = Variables::new();
+        variables.insert("PROJECT_NAME".to_string(), "test-project".into());
+        variables.insert("AUTHOR_NAME".to_string(), "Test Author".into());
 
         let template_replacements = vec![
             crate::template::Replacement {
@@ -300,25 +367,315 @@ mod tests {
         ];
 
         let content = "Project: PROJECT_PLACEHOLDER, Author: AUTHOR_PLACEHOLDER";
-        let result = apply_replacements(content, &global_replacements, &template_replacements);
+        let result = apply_replacements(content, &variables, &template_replacements);
 
         assert_eq!(result, "Project: test-project, Author: Test Author");
     }
 
     #[test]
     fn test_apply_replacements_with_custom_value() {
-        let global_replacements = HashMap::new();
+        let variables = Variables::new();
         let template_replacements = vec![crate::template::Replacement {
             placeholder: "CUSTOM_PLACEHOLDER".to_string(),
             value_type: ValueType::Custom("custom-value".to_string()),
         }];
 
         let content = "Custom: CUSTOM_PLACEHOLDER";
-        let result = apply_replacements(content, &global_replacements, &template_replacements);
+        let result = apply_replacements(content, &variables, &template_replacements);
 
         assert_eq!(result, "Custom: custom-value");
     }
 
+    #[test]
+    fn test_apply_replacements_with_custom_variable() {
+        let mut variables = Variables::new();
+        variables.insert("team".to_string(), "payments".into());
+        let template_replacements = vec![
+            crate::template::Replacement {
+                placeholder: "TEAM".to_string(),
+                value_type: ValueType::Custom("team".to_string()),
+            },
+            crate::template::Replacement {
+                placeholder: "REGION".to_string(),
+                value_type: ValueType::Custom("eu-west-1".to_string()),
+            },
+        ];
+
+        let content = "Team: TEAM, Region: REGION";
+        let result = apply_replacements(content, &variables, &template_replacements);
+
+        assert_eq!(result, "Team: payments, Region: eu-west-1");
+    }
+
+    /// Loader with the directory `template_dir` as the custom template "local"
+    async fn local_loader(root: &Path, template_dir: &Path) -> TemplateLoader {
+        let mut config = crate::config::Config::default();
+        config.templates.cache_directory = Some(root.join("cache"));
+        config
+            .templates
+            .custom
+            .push(local_template(template_dir.to_string_lossy()));
+        TemplateLoader::from_config(&config).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_create_project_prefers_template_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        let template_dir = temp_dir.path().join("template");
+        fs::create_dir_all(&template_dir).await.unwrap();
+        fs::write(
+            template_dir.join("app.toml"),
+            "name = \"my-app\"\nlicense = \"{{license}}\"\n",
+        )
+        .await
+        .unwrap();
+        fs::write(
+            template_dir.join(MANIFEST_FILE),
+            r#"
+[[files]]
+path = "app.toml"
+
+[[files.replacements]]
+placeholder = "my-app"
+value_type = "ProjectName"
+
+[[variables]]
+name = "license"
+default = "MIT"
+"#,
+        )
+        .await
+        .unwrap();
+
+        let loader = local_loader(temp_dir.path(), &template_dir).await;
+
+        create_project_with_loader(
+            &loader,
+            "local",
+            None,
+            "demo".to_string(),
+            Some(temp_dir.path().to_path_buf()),
+            true,
+            Variables::new(),
+        )
+        .await
+        .unwrap();
+
+        let project_dir = temp_dir.path().join("demo");
+        let content = fs::read_to_string(project_dir.join("app.toml"))
+            .await
+            .unwrap();
+        assert_eq!(content, "name = \"demo\"\nlicense = \"MIT\"\n");
+        assert!(!project_dir.join(MANIFEST_FILE).exists());
+    }
+
+    #[tokio::test]
+    async fn test_create_project_renders_templated_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let template_dir = temp_dir.path().join("template");
+        fs::create_dir_all(&template_dir).await.unwrap();
+        fs::write(
+            template_dir.join("Cargo.toml"),
+            "[package]\nname = \"{{ PROJECT_NAME | snake_case }}\"\n\n[dependencies]\n\
+             {% for crate in crates | split(\",\") %}\n{{ crate }} = \"*\"\n{% endfor %}\n\
+             {% if with_cli == \"yes\" %}\nclap = \"4\"\n{% endif %}\n",
+        )
+        .await
+        .unwrap();
+        fs::write(template_dir.join("notes.txt"), "{{ PROJECT_NAME }}")
+            .await
+            .unwrap();
+        fs::write(
+            template_dir.join(MANIFEST_FILE),
+            r#"
+[[files]]
+path = "Cargo.toml"
+render = true
+
+[[variables]]
+name = "crates"
+default = "serde,tokio"
+
+[[variables]]
+name = "with_cli"
+default = "no"
+"#,
+        )
+        .await
+        .unwrap();
+        let loader = local_loader(temp_dir.path(), &template_dir).await;
+
+        create_project_with_loader(
+            &loader,
+            "local",
+            None,
+            "my-service".to_string(),
+            Some(temp_dir.path().to_path_buf()),
+            true,
+            Variables::new(),
+        )
+        .await
+        .unwrap();
+
+        let project_dir = temp_dir.path().join("my-service");
+        let content = fs::read_to_string(project_dir.join("Cargo.toml"))
+            .await
+            .unwrap();
+        assert_eq!(
+            content,
+            "[package]\nname = \"my_service\"\n\n[dependencies]\nserde = \"*\"\ntokio = \"*\"\n"
+        );
+        // Only the files to customize are templates
+        let notes = fs::read_to_string(project_dir.join("notes.txt"))
+            .await
+            .unwrap();
+        assert_eq!(notes, "{{ PROJECT_NAME }}");
+    }
+
+    #[tokio::test]
+    async fn test_create_project_keeps_braces_in_files_not_rendered() {
+        let temp_dir = TempDir::new().unwrap();
+        let template_dir = temp_dir.path().join("template");
+        fs::create_dir_all(template_dir.join(".github"))
+            .await
+            .unwrap();
+        let workflow = "name: {{PROJECT_NAME}}\n\
+                        run: docker build -t app:${{ github.sha }} {% raw %}.\n";
+        fs::write(template_dir.join(".github/ci.yml"), workflow)
+            .await
+            .unwrap();
+        fs::write(
+            template_dir.join(MANIFEST_FILE),
+            "[[files]]\npath = \".github/ci.yml\"\n",
+        )
+        .await
+        .unwrap();
+        let loader = local_loader(temp_dir.path(), &template_dir).await;
+
+        create_project_with_loader(
+            &loader,
+            "local",
+            None,
+            "demo".to_string(),
+            Some(temp_dir.path().to_path_buf()),
+            true,
+            Variables::new(),
+        )
+        .await
+        .unwrap();
+
+        let content = fs::read_to_string(temp_dir.path().join("demo/.github/ci.yml"))
+            .await
+            .unwrap();
+        assert_eq!(
+            content,
+            "name: demo\nrun: docker build -t app:${{ github.sha }} {% raw %}.\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_project_reports_template_syntax_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let template_dir = temp_dir.path().join("template");
+        fs::create_dir_all(&template_dir).await.unwrap();
+        fs::write(
+            template_dir.join("README.md"),
+            "# {{ PROJECT_NAME }}\n\n{% if AUTHOR_NAME %}\nBy {{ AUTHOR_NAME }\n{% endif %}\n",
+        )
+        .await
+        .unwrap();
+        fs::write(
+            template_dir.join(MANIFEST_FILE),
+            "[[files]]\npath = \"README.md\"\nrender = true\n",
+        )
+        .await
+        .unwrap();
+        let loader = local_loader(temp_dir.path(), &template_dir).await;
+
+        let err = create_project_with_loader(
+            &loader,
+            "local",
+            None,
+            "demo".to_string(),
+            Some(temp_dir.path().to_path_buf()),
+            true,
+            Variables::new(),
+        )
+        .await
+        .unwrap_err();
+
+        match err.downcast_ref::<ClaudeForgeError>() {
+            Some(ClaudeForgeError::TemplateSyntax { path, line, .. }) => {
+                assert_eq!(path, Path::new("README.md"));
+                assert_eq!(*line, 4);
+            }
+            other => panic!("expected a template syntax error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_project_validates_variables_before_writing() {
+        let temp_dir = TempDir::new().unwrap();
+        let template_dir = temp_dir.path().join("template");
+        fs::create_dir_all(&template_dir).await.unwrap();
+        fs::write(template_dir.join("replicas.txt"), "{{ replicas + 1 }}")
+            .await
+            .unwrap();
+        fs::write(
+            template_dir.join(MANIFEST_FILE),
+            r#"
+[[files]]
+path = "replicas.txt"
+render = true
+
+[[variables]]
+name = "replicas"
+type = "integer"
+min = 1
+default = "2"
+"#,
+        )
+        .await
+        .unwrap();
+        let loader = local_loader(temp_dir.path(), &template_dir).await;
+
+        let mut variables = Variables::new();
+        variables.insert("replicas".to_string(), "0".into());
+        let err = create_project_with_loader(
+            &loader,
+            "local",
+            None,
+            "invalid".to_string(),
+            Some(temp_dir.path().to_path_buf()),
+            true,
+            variables,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ClaudeForgeError>(),
+            Some(ClaudeForgeError::VariableMin { name, .. }) if name == "replicas"
+        ));
+        assert!(!temp_dir.path().join("invalid").exists());
+
+        // Typed values reach the templates
+        create_project_with_loader(
+            &loader,
+            "local",
+            None,
+            "valid".to_string(),
+            Some(temp_dir.path().to_path_buf()),
+            true,
+            Variables::new(),
+        )
+        .await
+        .unwrap();
+        let content = fs::read_to_string(temp_dir.path().join("valid/replicas.txt"))
+            .await
+            .unwrap();
+        assert_eq!(content, "3");
+    }
+
     #[tokio::test]
     async fn test_initialize_git_repo() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,176 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::io::{BufRead, Write};
+
+use crate::template::engine::Variables;
+use crate::template::vars;
+use crate::template::{TemplateVariable, VariableType};
+
+/// Ask for the value of each declared variable that was not given on the
+/// command line. An empty answer takes the default; a variable with choices
+/// accepts one of them or its number. Invalid answers are asked for again.
+pub fn prompt_variables(
+    declared: &[TemplateVariable],
+    variables: &mut Variables,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> Result<()> {
+    for variable in declared {
+        if variables.contains_key(&variable.name) {
+            continue;
+        }
+        let value = prompt_variable(variable, input, output)?;
+        variables.insert(variable.name.clone(), value.into());
+    }
+    Ok(())
+}
+
+fn prompt_variable(
+    variable: &TemplateVariable,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> Result<String> {
+    if let Some(description) = &variable.description {
+        writeln!(output, "{description}")?;
+    }
+    for (i, choice) in variable.choices.iter().enumerate() {
+        writeln!(output, "  {}) {}", i + 1, choice)?;
+    }
+
+    let hint = match variable.var_type {
+        VariableType::Bool => " (yes/no)",
+        VariableType::Integer => " (integer)",
+        VariableType::List => " (comma separated)",
+        VariableType::String | VariableType::Choice => "",
+    };
+
+    loop {
+        match &variable.default {
+            Some(default) => write!(output, "{}{} [{}]: ", variable.name, hint, default)?,
+            None => write!(output, "{}{}: ", variable.name, hint)?,
+        }
+        output.flush()?;
+
+        let mut line = String::new();
+        let read = input
+            .read_line(&mut line)
+            .context("Failed to read from stdin")?;
+        let answer = line.trim();
+
+        let value = if answer.is_empty() {
+            variable.default.clone().unwrap_or_default()
+        } else {
+            find_choice(&variable.choices, answer)
+                .cloned()
+                .unwrap_or_else(|| answer.to_string())
+        };
+
+        // At the end of input the value is validated with all the others
+        // rather than asked for forever
+        if read == 0 {
+            return Ok(value);
+        }
+        match vars::validate_variable(variable, Some(&Value::from(value.as_str()))) {
+            Ok(_) => return Ok(value),
+            Err(e) => writeln!(output, "{e}")?,
+        }
+    }
+}
+
+/// The choice named or numbered by `answer`
+fn find_choice<'a>(choices: &'a [String], answer: &str) -> Option<&'a String> {
+    if let Ok(number) = answer.parse::<usize>() {
+        if let Some(choice) = number.checked_sub(1).and_then(|i| choices.get(i)) {
+            return Some(choice);
+        }
+    }
+    choices.iter().find(|choice| choice.as_str() == answer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Cursor;
+
+    fn variable(name: &str, default: Option<&str>, choices: &[&str]) -> TemplateVariable {
+        TemplateVariable {
+            name: name.to_string(),
+            description: Some(format!("The {name}")),
+            default: default.map(str::to_string),
+            choices: choices.iter().map(|c| c.to_string()).collect(),
+            ..TemplateVariable::default()
+        }
+    }
+
+    #[test]
+    fn test_prompt_variables_uses_answers_and_defaults() {
+        let declared = vec![
+            variable("team", None, &[]),
+            variable("license", Some("MIT"), &[]),
+            variable("database", Some("sqlite"), &["postgres", "sqlite"]),
+            variable("region", None, &[]),
+        ];
+        let mut variables = Variables::new();
+        variables.insert("region".to_string(), json!("eu"));
+        let mut input = Cursor::new("payments\n\nmysql\n1\n");
+        let mut output = Vec::new();
+
+        prompt_variables(&declared, &mut variables, &mut input, &mut output).unwrap();
+
+        assert_eq!(variables["team"], json!("payments"));
+        assert_eq!(variables["license"], json!("MIT"));
+        assert_eq!(variables["database"], json!("postgres"));
+        // Values given on the command line are not asked for
+        assert_eq!(variables["region"], json!("eu"));
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("The license\nlicense [MIT]: "));
+        assert!(output.contains("  1) postgres\n  2) sqlite\n"));
+        assert!(output.contains("Variable 'database' must be one of postgres, sqlite, got 'mysql'"));
+        assert!(!output.contains("region"));
+    }
+
+    #[test]
+    fn test_prompt_variables_asks_again_for_invalid_answers() {
+        let declared = vec![TemplateVariable {
+            name: "replicas".to_string(),
+            var_type: VariableType::Integer,
+            min: Some(1),
+            required: true,
+            ..TemplateVariable::default()
+        }];
+        let mut variables = Variables::new();
+        let mut input = Cursor::new("\nthree\n0\n3\n");
+        let mut output = Vec::new();
+
+        prompt_variables(&declared, &mut variables, &mut input, &mut output).unwrap();
+
+        assert_eq!(variables["replicas"], json!("3"));
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("replicas (integer): "));
+        assert!(output.contains("Variable 'replicas' is required"));
+        assert!(output.contains("Variable 'replicas' must be an integer, got 'three'"));
+        assert!(output.contains("Variable 'replicas' is below its min of 1 (value is 0)"));
+    }
+
+    #[test]
+    fn test_prompt_variables_end_of_input_keeps_defaults() {
+        let declared = vec![
+            variable("license", Some("MIT"), &[]),
+            variable("database", None, &["postgres", "sqlite"]),
+        ];
+        let mut variables = Variables::new();
+
+        prompt_variables(
+            &declared,
+            &mut variables,
+            &mut Cursor::new(""),
+            &mut Vec::new(),
+        )
+        .unwrap();
+
+        assert_eq!(variables["license"], json!("MIT"));
+        assert_eq!(variables["database"], json!(""));
+    }
+}
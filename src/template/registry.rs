@@ -1,18 +1,29 @@
-use crate::cli::Language;
+use crate::error::ClaudeForgeError;
 use crate::template::{FileCustomization, Replacement, Template, ValueType};
 use anyhow::Result;
+use serde::Deserialize;
 use std::collections::HashMap;
+use std::path::Path;
+use tokio::fs;
+
+/// On-disk layout of an external registry file
+#[derive(Debug, Deserialize)]
+struct RegistryFile {
+    #[serde(default)]
+    templates: Vec<Template>,
+}
 
 /// Load the built-in template registry
-pub fn load_template_registry() -> Result<HashMap<Language, Template>> {
+pub fn load_template_registry() -> Result<HashMap<String, Template>> {
     let mut templates = HashMap::new();
 
     // Rust template
     templates.insert(
-        Language::Rust,
+        "rust".to_string(),
         Template {
+            id: "rust".to_string(),
             name: "rust-claude-code".to_string(),
-            language: Language::Rust,
+            language: "rust".to_string(),
             repository: "https://github.com/iepathos/rust-claude-code".to_string(),
             description: "Comprehensive Rust starter template with Claude Code guidelines"
                 .to_string(),
@@ -23,6 +34,7 @@ pub fn load_template_registry() -> Result<HashMap<Language, Template>> {
                         placeholder: "my-project".to_string(),
                         value_type: ValueType::ProjectName,
                     }],
+                    render: false,
                 },
                 FileCustomization {
                     path: "README.md".to_string(),
@@ -36,17 +48,20 @@ pub fn load_template_registry() -> Result<HashMap<Language, Template>> {
                             value_type: ValueType::ProjectName,
                         },
                     ],
+                    render: false,
                 },
             ],
+            ..Template::default()
         },
     );
 
     // Go template
     templates.insert(
-        Language::Go,
+        "go".to_string(),
         Template {
+            id: "go".to_string(),
             name: "go-claude-code".to_string(),
-            language: Language::Go,
+            language: "go".to_string(),
             repository: "https://github.com/iepathos/go-claude-code".to_string(),
             description: "Go project template optimized for Claude Code development".to_string(),
             files_to_customize: vec![
@@ -56,6 +71,7 @@ pub fn load_template_registry() -> Result<HashMap<Language, Template>> {
                         placeholder: "github.com/yourusername/my-project".to_string(),
                         value_type: ValueType::Custom("github.com/user/project".to_string()),
                     }],
+                    render: false,
                 },
                 FileCustomization {
                     path: "README.md".to_string(),
@@ -69,17 +85,20 @@ pub fn load_template_registry() -> Result<HashMap<Language, Template>> {
                             value_type: ValueType::ProjectName,
                         },
                     ],
+                    render: false,
                 },
             ],
+            ..Template::default()
         },
     );
 
     // Python template
     templates.insert(
-        Language::Python,
+        "python".to_string(),
         Template {
+            id: "python".to_string(),
             name: "python-claude-code".to_string(),
-            language: Language::Python,
+            language: "python".to_string(),
             repository: "https://github.com/iepathos/python-claude-code".to_string(),
             description: "Comprehensive Python starter template with Claude Code guidelines"
                 .to_string(),
@@ -96,6 +115,7 @@ pub fn load_template_registry() -> Result<HashMap<Language, Template>> {
                             value_type: ValueType::AuthorName,
                         },
                     ],
+                    render: false,
                 },
                 FileCustomization {
                     path: "pyproject.toml".to_string(),
@@ -109,6 +129,7 @@ pub fn load_template_registry() -> Result<HashMap<Language, Template>> {
                             value_type: ValueType::AuthorName,
                         },
                     ],
+                    render: false,
                 },
                 FileCustomization {
                     path: "README.md".to_string(),
@@ -122,16 +143,120 @@ pub fn load_template_registry() -> Result<HashMap<Language, Template>> {
                             value_type: ValueType::ProjectName,
                         },
                     ],
+                    render: false,
                 },
             ],
+            ..Template::default()
         },
     );
 
     Ok(templates)
 }
 
-/// Load templates from a configuration file (future enhancement)
-pub async fn load_templates_from_config(_config_path: &str) -> Result<HashMap<Language, Template>> {
-    // TODO: Implement loading from external config file
-    load_template_registry()
+/// Parse a registry file, choosing JSON or TOML based on its extension
+pub async fn parse_registry_file(path: &Path) -> Result<Vec<Template>> {
+    let registry_error = |message: String| ClaudeForgeError::RegistryError {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let content = fs::read_to_string(path)
+        .await
+        .map_err(|e| registry_error(e.to_string()))?;
+
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+    Ok(parse_registry(&content, is_json).map_err(registry_error)?)
+}
+
+/// Parse registry contents, shared by registry files and remote indexes
+pub fn parse_registry(content: &str, is_json: bool) -> Result<Vec<Template>, String> {
+    let registry: RegistryFile = if is_json {
+        serde_json::from_str(content).map_err(|e| e.to_string())?
+    } else {
+        toml::from_str(content).map_err(|e| e.to_string())?
+    };
+
+    Ok(registry.templates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const TOML_REGISTRY: &str = r#"
+[[templates]]
+id = "rust"
+name = "team-rust"
+language = "rust"
+repository = "https://example.com/team/rust-template"
+description = "Team Rust template"
+
+[[templates.files_to_customize]]
+path = "Cargo.toml"
+
+[[templates.files_to_customize.replacements]]
+placeholder = "team-project"
+value_type = "ProjectName"
+"#;
+
+    #[tokio::test]
+    async fn test_parse_registry_file_toml() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("registry.toml");
+        fs::write(&path, TOML_REGISTRY).await.unwrap();
+
+        let templates = parse_registry_file(&path).await.unwrap();
+
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].id, "rust");
+        assert_eq!(templates[0].name, "team-rust");
+        assert_eq!(templates[0].files_to_customize.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_parse_registry_file_json() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("registry.json");
+        let json = r#"{
+            "templates": [{
+                "id": "go-grpc",
+                "name": "team-go",
+                "language": "go",
+                "repository": "https://example.com/team/go-template",
+                "description": "Team Go template"
+            }]
+        }"#;
+        fs::write(&path, json).await.unwrap();
+
+        let templates = parse_registry_file(&path).await.unwrap();
+
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].name, "team-go");
+        assert_eq!(templates[0].language, "go");
+        assert!(templates[0].files_to_customize.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_parse_registry_file_invalid() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("registry.toml");
+        fs::write(&path, "[[templates]]\nname = 42").await.unwrap();
+
+        let err = parse_registry_file(&path).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ClaudeForgeError>(),
+            Some(ClaudeForgeError::RegistryError { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_parse_registry_file_missing() {
+        let temp_dir = TempDir::new().unwrap();
+        let result = parse_registry_file(&temp_dir.path().join("missing.toml")).await;
+        assert!(result.is_err());
+    }
 }
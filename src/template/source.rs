@@ -0,0 +1,163 @@
+use std::path::PathBuf;
+
+use crate::utils::archive::ArchiveFormat;
+
+/// Where a template's files come from, derived from `Template::repository`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateSource {
+    /// Remote git repository, cloned into the template cache
+    Git(String),
+    /// Local directory used in place, without caching
+    Local(PathBuf),
+    /// `.tar.gz`/`.zip` archive, unpacked into the template cache
+    Archive {
+        location: ArchiveLocation,
+        format: ArchiveFormat,
+    },
+}
+
+/// Where an archive is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveLocation {
+    /// Downloaded over HTTP(S)
+    Url(String),
+    /// Read from the local filesystem
+    Path(PathBuf),
+}
+
+impl TemplateSource {
+    /// Classify a repository string. Anything ending in an archive extension is
+    /// an archive, local paths ending in `.git` are git repositories, other
+    /// `file://` URLs and plain paths are local directories, and URLs with a
+    /// scheme and scp-style `user@host:path` are git.
+    pub fn parse(repository: &str) -> Self {
+        if let Some(format) = ArchiveFormat::detect(repository) {
+            let location =
+                if repository.starts_with("http://") || repository.starts_with("https://") {
+                    ArchiveLocation::Url(repository.to_string())
+                } else {
+                    let path = repository.strip_prefix("file://").unwrap_or(repository);
+                    ArchiveLocation::Path(expand_home(path))
+                };
+            return TemplateSource::Archive { location, format };
+        }
+
+        if let Some(path) = repository.strip_prefix("file://") {
+            if is_git_path(path) {
+                return TemplateSource::Git(repository.to_string());
+            }
+            return TemplateSource::Local(expand_home(path));
+        }
+
+        if repository.contains("://") || is_scp_like(repository) {
+            return TemplateSource::Git(repository.to_string());
+        }
+
+        let path = expand_home(repository);
+        if is_git_path(repository) {
+            // Local repositories named `*.git` are cloned like remote ones
+            TemplateSource::Git(path.to_string_lossy().into_owned())
+        } else {
+            TemplateSource::Local(path)
+        }
+    }
+
+    pub fn is_local(&self) -> bool {
+        matches!(self, TemplateSource::Local(_))
+    }
+}
+
+fn is_git_path(path: &str) -> bool {
+    path.trim_end_matches('/').ends_with(".git")
+}
+
+/// `git@github.com:user/repo.git` style addresses
+fn is_scp_like(repository: &str) -> bool {
+    match (repository.find('@'), repository.find(':')) {
+        (Some(at), Some(colon)) => at < colon && !repository[..colon].contains('/'),
+        _ => false,
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_git_sources() {
+        for url in [
+            "https://github.com/iepathos/rust-claude-code",
+            "ssh://git@example.com/team/template.git",
+            "git@github.com:iepathos/rust-claude-code.git",
+        ] {
+            assert_eq!(TemplateSource::parse(url), TemplateSource::Git(url.into()));
+        }
+    }
+
+    #[test]
+    fn test_parse_local_sources() {
+        assert_eq!(
+            TemplateSource::parse("file:///srv/templates/rust"),
+            TemplateSource::Local("/srv/templates/rust".into())
+        );
+        assert_eq!(
+            TemplateSource::parse("/srv/templates/rust"),
+            TemplateSource::Local("/srv/templates/rust".into())
+        );
+        assert_eq!(
+            TemplateSource::parse("../templates/rust"),
+            TemplateSource::Local("../templates/rust".into())
+        );
+        assert!(TemplateSource::parse("templates/user@host:weird").is_local());
+    }
+
+    #[test]
+    fn test_parse_local_git_repositories() {
+        assert_eq!(
+            TemplateSource::parse("/srv/git/rust-template.git"),
+            TemplateSource::Git("/srv/git/rust-template.git".into())
+        );
+        assert_eq!(
+            TemplateSource::parse("file:///srv/git/rust-template.git"),
+            TemplateSource::Git("file:///srv/git/rust-template.git".into())
+        );
+    }
+
+    #[test]
+    fn test_parse_archive_sources() {
+        assert_eq!(
+            TemplateSource::parse("https://files.example.com/rust-template.tar.gz"),
+            TemplateSource::Archive {
+                location: ArchiveLocation::Url(
+                    "https://files.example.com/rust-template.tar.gz".into()
+                ),
+                format: ArchiveFormat::TarGz,
+            }
+        );
+        assert_eq!(
+            TemplateSource::parse("file:///srv/templates/go.zip"),
+            TemplateSource::Archive {
+                location: ArchiveLocation::Path("/srv/templates/go.zip".into()),
+                format: ArchiveFormat::Zip,
+            }
+        );
+        assert!(!TemplateSource::parse("/srv/templates/go.tgz").is_local());
+    }
+
+    #[test]
+    fn test_parse_expands_home() {
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(
+                TemplateSource::parse("~/templates/rust"),
+                TemplateSource::Local(home.join("templates/rust"))
+            );
+        }
+    }
+}
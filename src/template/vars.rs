@@ -0,0 +1,439 @@
+use anyhow::Result;
+use regex::Regex;
+use serde_json::Value;
+use std::path::Path;
+use tokio::fs;
+use tracing::debug;
+
+use crate::error::ClaudeForgeError;
+use crate::template::engine::Variables;
+use crate::template::{TemplateVariable, VariableType};
+
+/// Read template variables from a file, choosing JSON, YAML or TOML based on
+/// its extension. The file must hold a single table of variables.
+pub async fn load_vars_file(path: &Path) -> Result<Variables> {
+    let vars_error = |message: String| ClaudeForgeError::InvalidVarsFile {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let content = fs::read_to_string(path)
+        .await
+        .map_err(|e| vars_error(e.to_string()))?;
+
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+    let variables = match extension.as_deref() {
+        Some("json") => serde_json::from_str(&content).map_err(|e| e.to_string()),
+        Some("yaml" | "yml") => serde_yaml::from_str(&content).map_err(|e| e.to_string()),
+        _ => toml::from_str(&content).map_err(|e| e.to_string()),
+    }
+    .map_err(vars_error)?;
+
+    debug!("Loaded template variables from {:?}", path);
+    Ok(variables)
+}
+
+/// Merge variables given on the command line over those of a vars file
+pub fn merge_vars(mut variables: Variables, overrides: Vec<(String, String)>) -> Variables {
+    for (name, value) in overrides {
+        variables.insert(name, value.into());
+    }
+    variables
+}
+
+/// Text of a variable as used by literal replacements
+pub fn value_to_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Check the variables a template declares, falling back to their defaults
+/// for missing or empty values, and convert them to the declared types
+pub fn validate_variables(
+    declared: &[TemplateVariable],
+    variables: &mut Variables,
+) -> Result<(), ClaudeForgeError> {
+    for variable in declared {
+        // An empty value takes the default, as an empty answer to a prompt does
+        let value = variables
+            .get(&variable.name)
+            .filter(|value| !is_empty(value))
+            .cloned()
+            .or_else(|| variable.default.clone().map(Value::from));
+        if let Some(value) = validate_variable(variable, value.as_ref())? {
+            variables.insert(variable.name.clone(), value);
+        }
+    }
+    Ok(())
+}
+
+/// Check one value of a declared variable, returning it converted to the
+/// variable's type, or `None` when it is empty and not required
+pub fn validate_variable(
+    variable: &TemplateVariable,
+    value: Option<&Value>,
+) -> Result<Option<Value>, ClaudeForgeError> {
+    let name = &variable.name;
+    if variable.var_type == VariableType::Choice && variable.choices.is_empty() {
+        return Err(ClaudeForgeError::TemplateValidation(format!(
+            "variable '{name}' is a choice but declares no choices"
+        )));
+    }
+
+    let value = match value {
+        Some(value) if !is_empty(value) => value,
+        _ if variable.required => return Err(ClaudeForgeError::VariableRequired(name.clone())),
+        _ => return Ok(None),
+    };
+
+    let type_error = |expected| ClaudeForgeError::VariableType {
+        name: name.clone(),
+        expected,
+        value: value_to_string(value),
+    };
+    let typed = match variable.var_type {
+        VariableType::String | VariableType::Choice => match value {
+            Value::Array(_) | Value::Object(_) => return Err(type_error("a string")),
+            other => Value::String(value_to_string(other)),
+        },
+        VariableType::Bool => {
+            Value::Bool(parse_bool(value).ok_or_else(|| type_error("a boolean"))?)
+        }
+        VariableType::Integer => {
+            Value::from(parse_integer(value).ok_or_else(|| type_error("an integer"))?)
+        }
+        VariableType::List => match value {
+            Value::Array(items) if items.iter().all(|i| !i.is_array() && !i.is_object()) => items
+                .iter()
+                .map(|i| Value::String(value_to_string(i)))
+                .collect(),
+            Value::Array(_) | Value::Object(_) => return Err(type_error("a list of strings")),
+            other => value_to_string(other)
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(Value::from)
+                .collect(),
+        },
+    };
+
+    let items: Vec<&str> = match &typed {
+        Value::String(s) => vec![s.as_str()],
+        Value::Array(items) => items.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+
+    if !variable.choices.is_empty() {
+        if let Some(item) = items
+            .iter()
+            .find(|item| !variable.choices.iter().any(|c| c == *item))
+        {
+            return Err(ClaudeForgeError::VariableChoice {
+                name: name.clone(),
+                choices: variable.choices.join(", "),
+                value: item.to_string(),
+            });
+        }
+    }
+
+    if let Some(pattern) = &variable.pattern {
+        // The pattern must match the whole value, not just a part of it
+        let regex = Regex::new(&format!("^(?:{pattern})$")).map_err(|e| {
+            ClaudeForgeError::TemplateValidation(format!(
+                "variable '{name}' has an invalid pattern: {e}"
+            ))
+        })?;
+        if let Some(item) = items.iter().find(|item| !regex.is_match(item)) {
+            return Err(ClaudeForgeError::VariablePattern {
+                name: name.clone(),
+                pattern: pattern.clone(),
+                value: item.to_string(),
+            });
+        }
+    }
+
+    let measured = match &typed {
+        Value::Number(n) => n.as_i64().map(|n| ("value", n)),
+        Value::String(s) => Some(("length", s.chars().count() as i64)),
+        Value::Array(items) => Some(("number of items", items.len() as i64)),
+        _ => None,
+    };
+    if let Some((measure, actual)) = measured {
+        if let Some(min) = variable.min.filter(|min| actual < *min) {
+            return Err(ClaudeForgeError::VariableMin {
+                name: name.clone(),
+                min,
+                measure,
+                actual,
+            });
+        }
+        if let Some(max) = variable.max.filter(|max| actual > *max) {
+            return Err(ClaudeForgeError::VariableMax {
+                name: name.clone(),
+                max,
+                measure,
+                actual,
+            });
+        }
+    }
+
+    Ok(Some(typed))
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.trim().is_empty(),
+        Value::Array(items) => items.is_empty(),
+        _ => false,
+    }
+}
+
+fn parse_bool(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" | "y" | "on" | "1" => Some(true),
+            "false" | "no" | "n" | "off" | "0" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn parse_integer(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_load_vars_file_formats() {
+        let temp_dir = TempDir::new().unwrap();
+        let files = [
+            (
+                "vars.toml",
+                "team = \"payments\"\nreplicas = 3\nregions = [\"eu\", \"us\"]\n",
+            ),
+            (
+                "vars.json",
+                r#"{"team": "payments", "replicas": 3, "regions": ["eu", "us"]}"#,
+            ),
+            (
+                "vars.yaml",
+                "team: payments\nreplicas: 3\nregions:\n  - eu\n  - us\n",
+            ),
+        ];
+
+        for (name, content) in files {
+            let path = temp_dir.path().join(name);
+            fs::write(&path, content).await.unwrap();
+            let variables = load_vars_file(&path).await.unwrap();
+
+            assert_eq!(variables["team"], json!("payments"), "{name}");
+            assert_eq!(variables["replicas"], json!(3), "{name}");
+            assert_eq!(variables["regions"], json!(["eu", "us"]), "{name}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_load_vars_file_invalid_or_missing() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("vars.yml");
+        fs::write(&path, "- not\n- a\n- table\n").await.unwrap();
+
+        for path in [path, temp_dir.path().join("missing.toml")] {
+            let err = load_vars_file(&path).await.unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<ClaudeForgeError>(),
+                Some(ClaudeForgeError::InvalidVarsFile { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_merge_vars_prefers_command_line() {
+        let mut variables = Variables::new();
+        variables.insert("team".to_string(), json!("payments"));
+        variables.insert("replicas".to_string(), json!(3));
+
+        let merged = merge_vars(variables, vec![("team".to_string(), "search".to_string())]);
+
+        assert_eq!(merged["team"], json!("search"));
+        assert_eq!(value_to_string(&merged["replicas"]), "3");
+    }
+
+    fn declared(name: &str, var_type: VariableType) -> TemplateVariable {
+        TemplateVariable {
+            name: name.to_string(),
+            var_type,
+            ..TemplateVariable::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_variables_converts_types() {
+        let declared = vec![
+            declared("tls", VariableType::Bool),
+            declared("replicas", VariableType::Integer),
+            declared("regions", VariableType::List),
+            declared("owners", VariableType::List),
+            TemplateVariable {
+                default: Some("postgres".to_string()),
+                choices: vec!["postgres".to_string(), "sqlite".to_string()],
+                ..declared("database", VariableType::Choice)
+            },
+            declared("optional", VariableType::String),
+        ];
+        let mut variables = Variables::new();
+        variables.insert("tls".to_string(), json!("yes"));
+        variables.insert("replicas".to_string(), json!("3"));
+        variables.insert("regions".to_string(), json!("eu, us"));
+        variables.insert("owners".to_string(), json!(["ann", "bob"]));
+
+        validate_variables(&declared, &mut variables).unwrap();
+
+        assert_eq!(variables["tls"], json!(true));
+        assert_eq!(variables["replicas"], json!(3));
+        assert_eq!(variables["regions"], json!(["eu", "us"]));
+        assert_eq!(variables["owners"], json!(["ann", "bob"]));
+        assert_eq!(variables["database"], json!("postgres"));
+        assert!(!variables.contains_key("optional"));
+    }
+
+    #[test]
+    fn test_validate_variables_empty_value_takes_default() {
+        let declared = vec![
+            TemplateVariable {
+                default: Some("2".to_string()),
+                ..declared("replicas", VariableType::Integer)
+            },
+            declared("optional", VariableType::String),
+        ];
+        let mut variables = Variables::new();
+        variables.insert("replicas".to_string(), json!(""));
+        variables.insert("optional".to_string(), json!(""));
+
+        validate_variables(&declared, &mut variables).unwrap();
+
+        assert_eq!(variables["replicas"], json!(2));
+        assert_eq!(variables["optional"], json!(""));
+    }
+
+    #[test]
+    fn test_validate_variable_rules() {
+        let check = |variable: &TemplateVariable, value: Value| {
+            validate_variable(variable, Some(&value)).unwrap_err()
+        };
+
+        let required = TemplateVariable {
+            required: true,
+            ..declared("team", VariableType::String)
+        };
+        assert!(matches!(
+            validate_variable(&required, None),
+            Err(ClaudeForgeError::VariableRequired(name)) if name == "team"
+        ));
+        assert!(matches!(
+            check(&required, json!("  ")),
+            ClaudeForgeError::VariableRequired(_)
+        ));
+
+        assert!(matches!(
+            check(&declared("tls", VariableType::Bool), json!("maybe")),
+            ClaudeForgeError::VariableType {
+                expected: "a boolean",
+                ..
+            }
+        ));
+        assert!(matches!(
+            check(&declared("team", VariableType::String), json!(["a"])),
+            ClaudeForgeError::VariableType {
+                expected: "a string",
+                ..
+            }
+        ));
+
+        let choice = TemplateVariable {
+            choices: vec!["eu".to_string(), "us".to_string()],
+            ..declared("regions", VariableType::List)
+        };
+        assert!(matches!(
+            check(&choice, json!("eu,ap")),
+            ClaudeForgeError::VariableChoice { value, .. } if value == "ap"
+        ));
+
+        let pattern = TemplateVariable {
+            pattern: Some("[a-z][a-z0-9-]*".to_string()),
+            ..declared("service", VariableType::String)
+        };
+        assert!(validate_variable(&pattern, Some(&json!("billing-2"))).is_ok());
+        // The whole value must match
+        let err = check(&pattern, json!("billing_2"));
+        assert!(matches!(err, ClaudeForgeError::VariablePattern { .. }));
+        assert_eq!(
+            err.to_string(),
+            "Variable 'service' must match the pattern '[a-z][a-z0-9-]*', got 'billing_2'"
+        );
+
+        let bounded = TemplateVariable {
+            min: Some(1),
+            max: Some(5),
+            ..declared("replicas", VariableType::Integer)
+        };
+        assert!(matches!(
+            check(&bounded, json!(0)),
+            ClaudeForgeError::VariableMin {
+                min: 1,
+                actual: 0,
+                ..
+            }
+        ));
+        assert!(matches!(
+            check(&bounded, json!("9")),
+            ClaudeForgeError::VariableMax {
+                max: 5,
+                actual: 9,
+                ..
+            }
+        ));
+        let short = TemplateVariable {
+            max: Some(3),
+            ..declared("code", VariableType::String)
+        };
+        assert_eq!(
+            check(&short, json!("abcd")).to_string(),
+            "Variable 'code' is above its max of 3 (length is 4)"
+        );
+    }
+
+    #[test]
+    fn test_validate_variable_invalid_declarations() {
+        let choice = declared("database", VariableType::Choice);
+        assert!(matches!(
+            validate_variable(&choice, None),
+            Err(ClaudeForgeError::TemplateValidation(_))
+        ));
+
+        let pattern = TemplateVariable {
+            pattern: Some("(".to_string()),
+            ..declared("service", VariableType::String)
+        };
+        assert!(matches!(
+            validate_variable(&pattern, Some(&json!("billing"))),
+            Err(ClaudeForgeError::TemplateValidation(_))
+        ));
+    }
+}
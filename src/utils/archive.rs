@@ -0,0 +1,175 @@
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// Supported template archive formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    TarGz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Detect the format from a file name or URL, ignoring any query string
+    pub fn detect(location: &str) -> Option<Self> {
+        let path = location.split(['?', '#']).next().unwrap_or_default();
+        let path = path.to_ascii_lowercase();
+
+        if path.ends_with(".tar.gz") || path.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if path.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// Extract an archive into `dest`, which must not exist yet.
+///
+/// Archives that wrap everything in a single top-level directory (as GitHub
+/// release tarballs do) are unwrapped so `dest` holds the template files.
+pub async fn extract_archive(archive: &Path, format: ArchiveFormat, dest: &Path) -> Result<()> {
+    let archive = archive.to_path_buf();
+    let dest = dest.to_path_buf();
+
+    tokio::task::spawn_blocking(move || extract_archive_blocking(&archive, format, &dest)).await?
+}
+
+fn extract_archive_blocking(archive: &Path, format: ArchiveFormat, dest: &Path) -> Result<()> {
+    let parent = dest
+        .parent()
+        .with_context(|| format!("Invalid extraction target: {dest:?}"))?;
+    std::fs::create_dir_all(parent)?;
+
+    let staging = tempfile::Builder::new()
+        .prefix(".extract-")
+        .tempdir_in(parent)
+        .with_context(|| format!("Failed to create staging directory in {parent:?}"))?;
+
+    let file =
+        File::open(archive).with_context(|| format!("Failed to open archive: {archive:?}"))?;
+
+    match format {
+        ArchiveFormat::TarGz => {
+            let decoder = flate2::read::GzDecoder::new(file);
+            tar::Archive::new(decoder)
+                .unpack(staging.path())
+                .with_context(|| format!("Failed to extract tar archive: {archive:?}"))?;
+        }
+        ArchiveFormat::Zip => {
+            zip::ZipArchive::new(file)
+                .and_then(|mut zip| zip.extract(staging.path()))
+                .with_context(|| format!("Failed to extract zip archive: {archive:?}"))?;
+        }
+    }
+
+    let root = archive_root(staging.path())?;
+    std::fs::rename(&root, dest)
+        .with_context(|| format!("Failed to move extracted template to {dest:?}"))?;
+
+    Ok(())
+}
+
+/// The directory holding the archive contents, skipping a single wrapper directory
+fn archive_root(extracted: &Path) -> Result<PathBuf> {
+    let entries: Vec<_> = std::fs::read_dir(extracted)?.collect::<std::io::Result<_>>()?;
+
+    match entries.as_slice() {
+        [only] if only.file_type()?.is_dir() => Ok(only.path()),
+        _ => Ok(extracted.to_path_buf()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn write_tar_gz(path: &Path, prefix: &str) {
+        let file = File::create(path).unwrap();
+        let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+
+        let content = b"[package]";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, format!("{prefix}Cargo.toml"), &content[..])
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(
+            ArchiveFormat::detect("https://example.com/t.tar.gz?token=1"),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(
+            ArchiveFormat::detect("/srv/t.TGZ"),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(ArchiveFormat::detect("t.zip"), Some(ArchiveFormat::Zip));
+        assert_eq!(
+            ArchiveFormat::detect("https://github.com/iepathos/rust-claude-code"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_tar_gz_strips_wrapper_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("template.tar.gz");
+        write_tar_gz(&archive, "rust-template-main/");
+
+        let dest = temp_dir.path().join("cache").join("rust");
+        extract_archive(&archive, ArchiveFormat::TarGz, &dest)
+            .await
+            .unwrap();
+
+        assert!(dest.join("Cargo.toml").exists());
+        // Only the extracted template remains next to it
+        let siblings = std::fs::read_dir(temp_dir.path().join("cache")).unwrap();
+        assert_eq!(siblings.count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_extract_zip() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("template.zip");
+
+        let mut zip = zip::ZipWriter::new(File::create(&archive).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("README.md", options).unwrap();
+        zip.write_all(b"# template").unwrap();
+        zip.add_directory("src/", options).unwrap();
+        zip.start_file("src/main.rs", options).unwrap();
+        zip.write_all(b"fn main() {}").unwrap();
+        zip.finish().unwrap();
+
+        let dest = temp_dir.path().join("zip-template");
+        extract_archive(&archive, ArchiveFormat::Zip, &dest)
+            .await
+            .unwrap();
+
+        assert!(dest.join("README.md").exists());
+        assert!(dest.join("src").join("main.rs").exists());
+    }
+
+    #[tokio::test]
+    async fn test_extract_invalid_archive() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("broken.tar.gz");
+        std::fs::write(&archive, "not an archive").unwrap();
+
+        let dest = temp_dir.path().join("broken");
+        let result = extract_archive(&archive, ArchiveFormat::TarGz, &dest).await;
+
+        assert!(result.is_err());
+        assert!(!dest.exists());
+    }
+}
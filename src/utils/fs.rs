@@ -1,6 +1,8 @@
 use anyhow::{Context, Result};
-use std::path::Path;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
 use tokio::fs;
+use tracing::warn;
 
 #[cfg(windows)]
 use tokio::time::{sleep, Duration};
@@ -50,6 +52,29 @@ async fn copy_dir_recursive_inner(src: &Path, dst: &Path, exclude: Option<&[&str
     Ok(())
 }
 
+/// Total size in bytes of the files below a directory
+pub async fn dir_size(path: &Path) -> Result<u64> {
+    Box::pin(dir_size_inner(path)).await
+}
+
+async fn dir_size_inner(path: &Path) -> Result<u64> {
+    let mut size = 0;
+    let mut entries = fs::read_dir(path)
+        .await
+        .with_context(|| format!("Failed to read directory: {path:?}"))?;
+
+    while let Some(entry) = entries.next_entry().await? {
+        let file_type = entry.file_type().await?;
+        if file_type.is_dir() {
+            size += Box::pin(dir_size_inner(&entry.path())).await?;
+        } else if file_type.is_file() {
+            size += entry.metadata().await?.len();
+        }
+    }
+
+    Ok(size)
+}
+
 /// Check if a directory is empty
 pub async fn is_dir_empty(path: &Path) -> Result<bool> {
     let mut entries = fs::read_dir(path).await?;
@@ -114,6 +139,84 @@ pub async fn remove_dir_all_robust(path: &Path) -> Result<()> {
     }
 }
 
+/// Replace `target` with the directory `staged`. The previous directory is
+/// moved to its [`backup_path`] and only deleted once the new one is in
+/// place, so `target` never holds a partial copy.
+pub async fn replace_dir(staged: &Path, target: &Path) -> Result<()> {
+    let backup = backup_path(target);
+    remove_dir_all_robust(&backup).await?;
+
+    let had_previous = target.exists();
+    if had_previous {
+        fs::rename(target, &backup)
+            .await
+            .with_context(|| format!("Failed to move {target:?} aside"))?;
+    }
+
+    if let Err(e) = fs::rename(staged, target).await {
+        if had_previous {
+            let _ = fs::rename(&backup, target).await;
+        }
+        return Err(e).with_context(|| format!("Failed to move {staged:?} to {target:?}"));
+    }
+
+    if had_previous {
+        if let Err(e) = remove_dir_all_robust(&backup).await {
+            warn!("Could not remove old directory {:?}: {}", backup, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Where [`replace_dir`] keeps the previous contents of `target` during a swap
+pub fn backup_path(target: &Path) -> PathBuf {
+    let mut file_name = OsString::from(".backup-");
+    file_name.push(target.file_name().unwrap_or_default());
+    target.with_file_name(file_name)
+}
+
+/// Readable and unique cache entry name for a repository or URL, e.g.
+/// `rust-claude-code-1f3a9c07`
+pub fn cache_key(location: &str) -> String {
+    let name = location
+        .trim_end_matches('/')
+        .rsplit(['/', '\\', ':'])
+        .next()
+        .unwrap_or_default();
+    let name = [".git", ".tar.gz", ".tgz", ".zip"]
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+        .unwrap_or(name);
+
+    format!("{}-{}", sanitize_path_component(name), short_hash(location))
+}
+
+/// Readable directory name part that stays unique for values that sanitize
+/// alike, e.g. `feature-x-0b5e1a2c` for `feature/x`
+pub fn unique_path_component(value: &str) -> String {
+    format!("{}-{}", sanitize_path_component(value), short_hash(value))
+}
+
+fn short_hash(value: &str) -> String {
+    // FNV-1a keeps the key stable across builds, unlike `DefaultHasher`
+    let hash = value.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+    format!("{:08x}", hash as u32)
+}
+
+/// Replace anything that is unsafe in a directory name
+pub fn sanitize_path_component(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => c,
+            _ => '-',
+        })
+        .collect()
+}
+
 #[cfg(windows)]
 async fn remove_readonly_attributes(path: &Path) -> Result<()> {
     use std::process::Command;
@@ -206,6 +309,38 @@ mod tests {
         assert!(!dst_dir.join(".git").exists());
     }
 
+    #[tokio::test]
+    async fn test_replace_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let target = temp_dir.path().join("target");
+        let staged = temp_dir.path().join("staged");
+
+        fs::create_dir_all(&staged).await.unwrap();
+        fs::write(staged.join("file.txt"), "v1").await.unwrap();
+        replace_dir(&staged, &target).await.unwrap();
+        assert_eq!(
+            fs::read_to_string(target.join("file.txt")).await.unwrap(),
+            "v1"
+        );
+
+        fs::create_dir_all(&staged).await.unwrap();
+        fs::write(staged.join("file.txt"), "v2").await.unwrap();
+        replace_dir(&staged, &target).await.unwrap();
+        assert_eq!(
+            fs::read_to_string(target.join("file.txt")).await.unwrap(),
+            "v2"
+        );
+        assert!(!staged.exists());
+        assert!(!backup_path(&target).exists());
+
+        // A failed swap leaves the previous directory in place
+        assert!(replace_dir(&staged, &target).await.is_err());
+        assert_eq!(
+            fs::read_to_string(target.join("file.txt")).await.unwrap(),
+            "v2"
+        );
+    }
+
     #[tokio::test]
     async fn test_is_dir_empty_with_empty_directory() {
         let temp_dir = TempDir::new().unwrap();
@@ -294,6 +429,29 @@ mod tests {
         assert!(dst_dir.join("existing_file.txt").exists());
     }
 
+    #[test]
+    fn test_cache_key() {
+        let key = cache_key("https://github.com/iepathos/rust-claude-code.git");
+        assert!(key.starts_with("rust-claude-code-"));
+        assert_eq!(
+            key,
+            cache_key("https://github.com/iepathos/rust-claude-code.git")
+        );
+        assert_ne!(
+            key,
+            cache_key("https://gitlab.com/iepathos/rust-claude-code.git")
+        );
+        assert!(cache_key("https://files.example.com/go.tar.gz").starts_with("go-"));
+    }
+
+    #[test]
+    fn test_unique_path_component() {
+        let branch = unique_path_component("feature/x");
+        assert!(branch.starts_with("feature-x-"));
+        assert_ne!(branch, unique_path_component("feature-x"));
+        assert_eq!(branch, unique_path_component("feature/x"));
+    }
+
     #[tokio::test]
     async fn test_remove_dir_all_robust() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,83 @@
+use anyhow::{Context, Result};
+use fs4::{FileExt, TryLockError};
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+/// Advisory lock on a file, shared between claudeforge processes. The lock is
+/// released when the guard is dropped.
+#[derive(Debug)]
+pub struct FileLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl FileLock {
+    /// Wait for exclusive access
+    pub async fn exclusive(path: &Path) -> Result<Self> {
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            let file = OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(&path)
+                .with_context(|| format!("Failed to open lock file: {path:?}"))?;
+
+            match FileExt::try_lock(&file) {
+                Ok(()) => {}
+                Err(TryLockError::WouldBlock) => {
+                    info!(
+                        "Waiting for another claudeforge process to release {:?}",
+                        path
+                    );
+                    FileExt::lock(&file).with_context(|| format!("Failed to lock {path:?}"))?;
+                }
+                Err(TryLockError::Error(e)) => {
+                    return Err(e).with_context(|| format!("Failed to lock {path:?}"));
+                }
+            }
+
+            debug!("Locked {:?}", path);
+            Ok(Self { file, path })
+        })
+        .await?
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        if FileExt::unlock(&self.file).is_ok() {
+            debug!("Unlocked {:?}", self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_exclusive_lock_waits_for_release() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("entry.lock");
+
+        let first = FileLock::exclusive(&path).await.unwrap();
+
+        let waiter = tokio::spawn({
+            let path = path.clone();
+            async move { FileLock::exclusive(&path).await.unwrap() }
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!waiter.is_finished());
+
+        drop(first);
+        let exclusive = tokio::time::timeout(Duration::from_secs(5), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+        drop(exclusive);
+    }
+}
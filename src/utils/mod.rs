@@ -1 +1,5 @@
+pub mod archive;
 pub mod fs;
+pub mod lock;
+#[cfg(test)]
+pub mod testing;
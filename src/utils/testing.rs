@@ -0,0 +1,71 @@
+//! Helpers shared by the unit tests
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+
+use crate::template::Template;
+
+/// Custom template "local" fetched from `repository`
+pub fn local_template(repository: impl Into<String>) -> Template {
+    Template {
+        id: "local".to_string(),
+        name: "local".to_string(),
+        language: "rust".to_string(),
+        repository: repository.into(),
+        description: "Local template".to_string(),
+        ..Template::default()
+    }
+}
+
+/// Raw HTTP response with the given status line, extra headers and body
+pub fn http_response(status: &str, headers: &[(&str, &str)], body: &[u8]) -> Vec<u8> {
+    let mut response = format!("HTTP/1.1 {status}\r\n");
+    for (name, value) in headers {
+        response.push_str(&format!("{name}: {value}\r\n"));
+    }
+    response.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    ));
+
+    let mut response = response.into_bytes();
+    response.extend_from_slice(body);
+    response
+}
+
+/// Serve one response per connection, in order, then stop listening.
+/// Returns the URL of `path` and the lowercased text of each request.
+pub fn serve_http(responses: Vec<Vec<u8>>, path: &str) -> (String, mpsc::Receiver<String>) {
+    serve(responses, path, false)
+}
+
+/// Answer every connection with `response`
+pub fn serve_http_forever(response: Vec<u8>, path: &str) -> (String, mpsc::Receiver<String>) {
+    serve(vec![response], path, true)
+}
+
+fn serve(responses: Vec<Vec<u8>>, path: &str, repeat: bool) -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let last = responses.last().cloned().unwrap_or_default();
+        let mut responses = responses.into_iter();
+        loop {
+            let response = match responses.next() {
+                Some(response) => response,
+                None if repeat => last.clone(),
+                None => break,
+            };
+            let Ok((mut stream, _)) = listener.accept() else {
+                break;
+            };
+            let mut request = [0u8; 4096];
+            let read = stream.read(&mut request).unwrap_or(0);
+            let _ = sender.send(String::from_utf8_lossy(&request[..read]).to_lowercase());
+            let _ = stream.write_all(&response);
+        }
+    });
+    (format!("http://{addr}/{path}"), receiver)
+}
@@ -6,12 +6,12 @@ use tempfile::TempDir;
 async fn test_create_projects_for_all_templates() {
     let registry = load_template_registry().unwrap();
 
-    for (language, template) in registry.iter() {
+    for (id, template) in registry.iter() {
         let temp_dir = TempDir::new().unwrap();
-        let project_name = format!("test-{}-project", language.to_string().to_lowercase());
+        let project_name = format!("test-{id}-project");
 
         let result = create_project(
-            language.clone(),
+            id,
             project_name.clone(),
             Some(temp_dir.path().to_path_buf()),
             true, // skip prompts
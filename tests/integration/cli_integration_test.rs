@@ -128,6 +128,19 @@ fn test_cli_new_project_existing_directory_with_yes() {
         .stdout(predicate::str::contains("overwriting due to --yes flag"));
 }
 
+#[test]
+fn test_cli_new_with_misspelled_template() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut cmd = Command::cargo_bin("claudeforge").unwrap();
+
+    cmd.env("XDG_CACHE_HOME", temp_dir.path())
+        .args(["new", "pyhton", "test-project", "--directory"])
+        .arg(temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("did you mean 'python'?"));
+}
+
 #[test]
 fn test_cli_invalid_command() {
     let mut cmd = Command::cargo_bin("claudeforge").unwrap();
@@ -158,3 +171,475 @@ fn test_cli_help_short_flag() {
             "Create new projects optimized for Claude Code",
         ));
 }
+
+#[test]
+fn test_cli_custom_template_lifecycle() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_home = temp_dir.path().join("config");
+    let cache_home = temp_dir.path().join("cache");
+
+    // A local repository stands in for the remote template
+    let repo_dir = temp_dir.path().join("template-repo");
+    std::fs::create_dir_all(&repo_dir).unwrap();
+    std::fs::write(repo_dir.join("README.md"), "# team-project").unwrap();
+    claudeforge::git::init_repository(&repo_dir).unwrap();
+    claudeforge::git::add_all_and_commit(&repo_dir, "Initial template").unwrap();
+
+    let claudeforge = || {
+        let mut cmd = Command::cargo_bin("claudeforge").unwrap();
+        cmd.env("XDG_CONFIG_HOME", &config_home)
+            .env("XDG_CACHE_HOME", &cache_home);
+        cmd
+    };
+
+    claudeforge()
+        .args(["template", "add", "team-python", "python"])
+        .arg(&repo_dir)
+        .args(["--description", "Team Python template"])
+        .args(["--file", "README.md:team-project:ProjectName"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("added successfully"));
+
+    claudeforge()
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("team-python (python)"))
+        .stdout(predicate::str::contains("python-claude-code"));
+
+    claudeforge()
+        .args(["template", "show", "team-python"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Language: python"))
+        .stdout(predicate::str::contains("Team Python template"))
+        .stdout(predicate::str::contains(
+            "README.md: team-project -> ProjectName",
+        ));
+
+    claudeforge()
+        .args(["new", "team-python", "my-service", "--directory"])
+        .arg(temp_dir.path())
+        .assert()
+        .success();
+    let readme = std::fs::read_to_string(temp_dir.path().join("my-service/README.md")).unwrap();
+    assert_eq!(readme, "# my-service");
+
+    claudeforge()
+        .args(["template", "add", "team-python", "python"])
+        .arg(&repo_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Duplicate template"));
+
+    claudeforge()
+        .args(["template", "remove", "team-python"])
+        .assert()
+        .success();
+
+    claudeforge()
+        .args(["template", "show", "team-python"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Template not found"));
+}
+
+#[test]
+fn test_cli_template_add_unreachable_repository() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut cmd = Command::cargo_bin("claudeforge").unwrap();
+
+    cmd.env("XDG_CONFIG_HOME", temp_dir.path())
+        .args(["template", "add", "broken", "rust"])
+        .arg(temp_dir.path().join("does-not-exist"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Template validation failed"));
+}
+
+#[test]
+fn test_cli_new_with_pinned_ref() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let repo_dir = temp_dir.path().join("template.git");
+    std::fs::create_dir_all(&repo_dir).unwrap();
+    claudeforge::git::init_repository(&repo_dir).unwrap();
+    std::fs::write(repo_dir.join("VERSION"), "1").unwrap();
+    claudeforge::git::add_all_and_commit(&repo_dir, "v1").unwrap();
+    let repo = git2::Repository::open(&repo_dir).unwrap();
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    repo.tag_lightweight("v1", head.as_object(), false).unwrap();
+
+    let registry = temp_dir.path().join("registry.toml");
+    std::fs::write(
+        &registry,
+        format!(
+            "[[templates]]\nid = \"pinned\"\nname = \"pinned\"\nlanguage = \"rust\"\n\
+             repository = \"{}\"\ndescription = \"Pinned template\"\n",
+            repo_dir.display()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("claudeforge").unwrap();
+    cmd.env("XDG_CACHE_HOME", temp_dir.path().join("cache"))
+        .env("CLAUDEFORGE_REGISTRY", &registry)
+        .args(["new", "pinned", "my-project", "--ref", "v1", "--directory"])
+        .arg(temp_dir.path())
+        .assert()
+        .success();
+
+    let cached: Vec<String> = std::fs::read_dir(temp_dir.path().join("cache/claudeforge"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert!(cached.iter().any(|name| name.contains("@v1-")));
+    assert!(temp_dir.path().join("my-project/VERSION").exists());
+
+    let mut cmd = Command::cargo_bin("claudeforge").unwrap();
+    cmd.env("XDG_CACHE_HOME", temp_dir.path().join("cache"))
+        .env("CLAUDEFORGE_REGISTRY", &registry)
+        .args([
+            "new",
+            "pinned",
+            "other-project",
+            "--ref",
+            "v9",
+            "--directory",
+        ])
+        .arg(temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Git ref not found: v9"));
+}
+
+#[test]
+fn test_cli_new_with_vars() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let template_dir = temp_dir.path().join("template");
+    std::fs::create_dir_all(&template_dir).unwrap();
+    std::fs::write(
+        template_dir.join("service.toml"),
+        "team = \"TEAM_PLACEHOLDER\"\nreplicas = {{ replicas }}\n\
+         {% for region in regions %}\nregion = \"{{ region }}\"\n{% endfor %}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        template_dir.join(".claudeforge.toml"),
+        "[[files]]\npath = \"service.toml\"\nrender = true\n\n[[files.replacements]]\n\
+         placeholder = \"TEAM_PLACEHOLDER\"\nvalue_type = { Custom = \"team\" }\n",
+    )
+    .unwrap();
+
+    let registry = temp_dir.path().join("registry.toml");
+    std::fs::write(
+        &registry,
+        format!(
+            "[[templates]]\nid = \"service\"\nname = \"service\"\nlanguage = \"rust\"\n\
+             repository = \"{}\"\ndescription = \"Service template\"\n",
+            template_dir.display()
+        ),
+    )
+    .unwrap();
+    let vars_file = temp_dir.path().join("vars.yaml");
+    std::fs::write(&vars_file, "replicas: 2\nregions:\n  - eu\n  - us\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("claudeforge").unwrap();
+    cmd.env("XDG_CACHE_HOME", temp_dir.path().join("cache"))
+        .env("CLAUDEFORGE_REGISTRY", &registry)
+        .args(["new", "service", "billing", "--var", "team=payments"])
+        .args(["--var", "replicas=3", "--vars-file"])
+        .arg(&vars_file)
+        .arg("--directory")
+        .arg(temp_dir.path())
+        .assert()
+        .success();
+
+    let content = std::fs::read_to_string(temp_dir.path().join("billing/service.toml")).unwrap();
+    assert_eq!(
+        content,
+        "team = \"payments\"\nreplicas = 3\nregion = \"eu\"\nregion = \"us\"\n"
+    );
+}
+
+#[test]
+fn test_cli_new_rejects_invalid_vars() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let template_dir = temp_dir.path().join("template");
+    std::fs::create_dir_all(&template_dir).unwrap();
+    std::fs::write(
+        template_dir.join(".claudeforge.toml"),
+        "[[variables]]\nname = \"port\"\ntype = \"integer\"\nmax = 65535\n\n\
+         [[variables]]\nname = \"team\"\nrequired = true\npattern = \"[a-z]+\"\n",
+    )
+    .unwrap();
+    let registry = temp_dir.path().join("registry.toml");
+    std::fs::write(
+        &registry,
+        format!(
+            "[[templates]]\nid = \"service\"\nname = \"service\"\nlanguage = \"rust\"\n\
+             repository = \"{}\"\ndescription = \"Service template\"\n",
+            template_dir.display()
+        ),
+    )
+    .unwrap();
+
+    let cases = [
+        (
+            vec!["--var", "team=payments", "--var", "port=http"],
+            "Variable 'port' must be an integer, got 'http'",
+        ),
+        (
+            vec!["--var", "team=payments", "--var", "port=70000"],
+            "Variable 'port' is above its max of 65535",
+        ),
+        (
+            vec!["--var", "team=Payments"],
+            "Variable 'team' must match the pattern '[a-z]+'",
+        ),
+        (vec![], "Variable 'team' is required"),
+    ];
+    for (args, message) in cases {
+        let mut cmd = Command::cargo_bin("claudeforge").unwrap();
+        cmd.env("XDG_CACHE_HOME", temp_dir.path().join("cache"))
+            .env("CLAUDEFORGE_REGISTRY", &registry)
+            .args(["new", "service", "billing", "--directory"])
+            .arg(temp_dir.path())
+            .args(args)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(message));
+        assert!(!temp_dir.path().join("billing").exists());
+    }
+}
+
+#[test]
+fn test_cli_cache_commands() {
+    let temp_dir = TempDir::new().unwrap();
+    let cache_home = temp_dir.path().join("cache");
+
+    let repo_dir = temp_dir.path().join("template.git");
+    std::fs::create_dir_all(&repo_dir).unwrap();
+    std::fs::write(repo_dir.join("README.md"), "# template").unwrap();
+    claudeforge::git::init_repository(&repo_dir).unwrap();
+    claudeforge::git::add_all_and_commit(&repo_dir, "Initial template").unwrap();
+    let commit = git2::Repository::open(&repo_dir)
+        .unwrap()
+        .head()
+        .unwrap()
+        .peel_to_commit()
+        .unwrap()
+        .id()
+        .to_string();
+
+    let registry = temp_dir.path().join("registry.toml");
+    std::fs::write(
+        &registry,
+        format!(
+            "[[templates]]\nid = \"cached\"\nname = \"cached\"\nlanguage = \"rust\"\n\
+             repository = \"{}\"\ndescription = \"Cached template\"\n",
+            repo_dir.display()
+        ),
+    )
+    .unwrap();
+
+    let claudeforge = || {
+        let mut cmd = Command::cargo_bin("claudeforge").unwrap();
+        cmd.env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+            .env("XDG_CACHE_HOME", &cache_home)
+            .env("CLAUDEFORGE_REGISTRY", &registry);
+        cmd
+    };
+
+    claudeforge()
+        .args(["cache", "path"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            cache_home.join("claudeforge").to_string_lossy().as_ref(),
+        ));
+
+    claudeforge()
+        .args(["new", "cached", "my-project", "--directory"])
+        .arg(temp_dir.path())
+        .assert()
+        .success();
+
+    claudeforge()
+        .args(["cache", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cached"))
+        .stdout(predicate::str::contains(&commit[..8]));
+
+    claudeforge()
+        .args(["cache", "prune", "--older-than", "1d"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed 0 cached template(s)"));
+
+    claudeforge()
+        .args(["cache", "clean", "cached"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed 1 cached template(s)"));
+
+    claudeforge()
+        .args(["cache", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("The template cache is empty"));
+}
+
+#[test]
+fn test_cli_cache_clean_templates_sharing_repository() {
+    let temp_dir = TempDir::new().unwrap();
+    let cache_home = temp_dir.path().join("cache");
+
+    let repo_dir = temp_dir.path().join("template.git");
+    std::fs::create_dir_all(&repo_dir).unwrap();
+    std::fs::write(repo_dir.join("README.md"), "# template").unwrap();
+    claudeforge::git::init_repository(&repo_dir).unwrap();
+    claudeforge::git::add_all_and_commit(&repo_dir, "Initial template").unwrap();
+    let repo = git2::Repository::open(&repo_dir).unwrap();
+    let head = repo.head().unwrap().peel(git2::ObjectType::Commit).unwrap();
+    repo.tag_lightweight("v1", &head, false).unwrap();
+
+    let registry = temp_dir.path().join("registry.toml");
+    let mut entries = String::new();
+    for (id, git_ref) in [("latest", ""), ("pinned", "ref = \"v1\"\n")] {
+        entries.push_str(&format!(
+            "[[templates]]\nid = \"{id}\"\nname = \"{id}\"\nlanguage = \"rust\"\n\
+             repository = \"{}\"\n{git_ref}description = \"Shared repository\"\n",
+            repo_dir.display()
+        ));
+    }
+    std::fs::write(&registry, entries).unwrap();
+
+    let claudeforge = || {
+        let mut cmd = Command::cargo_bin("claudeforge").unwrap();
+        cmd.env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+            .env("XDG_CACHE_HOME", &cache_home)
+            .env("CLAUDEFORGE_REGISTRY", &registry);
+        cmd
+    };
+    let create_projects = || {
+        for id in ["latest", "pinned"] {
+            claudeforge()
+                .args(["new", id, &format!("{id}-project"), "--yes", "--directory"])
+                .arg(temp_dir.path())
+                .assert()
+                .success();
+        }
+    };
+
+    create_projects();
+    claudeforge()
+        .args(["cache", "clean", "pinned"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed 1 cached template(s)"));
+    claudeforge()
+        .args(["cache", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("latest"))
+        .stdout(predicate::str::contains("pinned").not());
+
+    create_projects();
+    claudeforge()
+        .args(["cache", "clean", "latest", "--repository"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed 2 cached template(s)"));
+}
+
+#[test]
+fn test_cli_new_offline_requires_cached_template() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut cmd = Command::cargo_bin("claudeforge").unwrap();
+
+    cmd.env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_CACHE_HOME", temp_dir.path().join("cache"))
+        .args(["--offline", "new", "rust", "my-project", "--directory"])
+        .arg(temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Template 'rust' is not cached and cannot be fetched in offline mode",
+        ));
+    assert!(!temp_dir.path().join("my-project").exists());
+}
+
+#[test]
+fn test_cli_list_shows_rewritten_repositories() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_dir = temp_dir.path().join("config").join("claudeforge");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("config.toml"),
+        r#"
+[[templates.url_rewrite]]
+prefix = "https://github.com/"
+replacement = "https://git.corp.example.com/github/"
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("claudeforge").unwrap();
+    cmd.env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_CACHE_HOME", temp_dir.path().join("cache"))
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Repository: https://git.corp.example.com/github/iepathos/rust-claude-code \
+             (rewritten from https://github.com/iepathos/rust-claude-code)",
+        ));
+}
+
+#[test]
+fn test_cli_runs_without_git_executable() {
+    let temp_dir = TempDir::new().unwrap();
+    let empty_path = temp_dir.path().join("bin");
+    std::fs::create_dir_all(&empty_path).unwrap();
+
+    let mut cmd = Command::cargo_bin("claudeforge").unwrap();
+    cmd.env("PATH", &empty_path)
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_CACHE_HOME", temp_dir.path().join("cache"))
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Available templates:"));
+}
+
+#[test]
+fn test_cli_commands_without_config_ignore_broken_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_dir = temp_dir.path().join("config").join("claudeforge");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("config.toml"), "[templates\n").unwrap();
+
+    let claudeforge = || {
+        let mut cmd = Command::cargo_bin("claudeforge").unwrap();
+        cmd.env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+            .env("XDG_CACHE_HOME", temp_dir.path().join("cache"));
+        cmd
+    };
+
+    claudeforge()
+        .arg("version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("claudeforge"));
+    claudeforge()
+        .args(["cache", "path"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("claudeforge"));
+    claudeforge().arg("list").assert().failure();
+}
@@ -1,4 +1,4 @@
-use claudeforge::config::{Config, Defaults, TemplateConfig};
+use claudeforge::config::{Config, Defaults, GitConfig, TemplateConfig};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
 use tempfile::TempDir;
@@ -143,10 +143,17 @@ fn test_config_cache_directory_custom() {
             author_email: None,
             default_directory: None,
         },
+        git: GitConfig::default(),
         templates: TemplateConfig {
             cache_directory: Some(PathBuf::from("/custom/cache")),
             auto_update: true,
             update_interval_days: 7,
+            update_concurrency: 4,
+            offline: false,
+            registry: None,
+            indexes: Vec::new(),
+            url_rewrite: Vec::new(),
+            custom: Vec::new(),
         },
     };
 
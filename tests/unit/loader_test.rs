@@ -1,4 +1,3 @@
-use claudeforge::cli::Language;
 use claudeforge::template::loader::TemplateLoader;
 use std::sync::Mutex;
 use tempfile::TempDir;
@@ -14,7 +13,7 @@ async fn test_template_loader_new() {
 }
 
 #[tokio::test]
-async fn test_get_template_valid_language() {
+async fn test_get_template_valid_id() {
     let loader = match TemplateLoader::new().await {
         Ok(loader) => loader,
         Err(e) => {
@@ -23,20 +22,20 @@ async fn test_get_template_valid_language() {
         }
     };
 
-    let rust_template = loader.get_template(Language::Rust);
+    let rust_template = loader.get_template("rust");
     assert!(rust_template.is_ok());
     let template = rust_template.unwrap();
-    assert_eq!(template.language, Language::Rust);
+    assert_eq!(template.language, "rust");
 
-    let go_template = loader.get_template(Language::Go);
+    let go_template = loader.get_template("go");
     assert!(go_template.is_ok());
     let template = go_template.unwrap();
-    assert_eq!(template.language, Language::Go);
+    assert_eq!(template.language, "go");
 
-    let python_template = loader.get_template(Language::Python);
+    let python_template = loader.get_template("python");
     assert!(python_template.is_ok());
     let template = python_template.unwrap();
-    assert_eq!(template.language, Language::Python);
+    assert_eq!(template.language, "python");
 }
 
 #[tokio::test]
@@ -54,11 +53,11 @@ async fn test_list_templates() {
     // Should have at least the registered templates
     assert!(!templates.is_empty());
 
-    // Check that we have all expected languages
-    let languages: Vec<Language> = templates.iter().map(|t| t.language.clone()).collect();
-    assert!(languages.contains(&Language::Rust));
-    assert!(languages.contains(&Language::Go));
-    assert!(languages.contains(&Language::Python));
+    // Check that we have all expected built-in ids
+    let ids: Vec<&str> = templates.iter().map(|t| t.id.as_str()).collect();
+    assert!(ids.contains(&"rust"));
+    assert!(ids.contains(&"go"));
+    assert!(ids.contains(&"python"));
 }
 
 #[tokio::test]
@@ -91,7 +90,7 @@ async fn test_get_or_fetch_with_cached_template() {
     };
 
     // This should use the cached template without fetching
-    let result = loader.get_or_fetch(Language::Rust).await;
+    let result = loader.get_or_fetch("rust").await;
 
     // Note: This might fail if it tries to fetch from the actual repository
     // In a real test environment, we'd mock the git operations
@@ -1,5 +1,5 @@
 use clap::Parser;
-use claudeforge::{Cli, Commands};
+use claudeforge::{CacheCommands, Cli, Commands};
 
 #[test]
 fn test_cli_parsing_new_command() {
@@ -16,12 +16,15 @@ fn test_cli_parsing_new_command() {
 
     match cli.command {
         Commands::New {
-            language,
+            template,
+            git_ref,
             name,
             directory,
             yes,
+            ..
         } => {
-            assert_eq!(language, claudeforge::cli::Language::Rust);
+            assert_eq!(template, "rust");
+            assert!(git_ref.is_none());
             assert_eq!(name, "my-project");
             assert_eq!(directory, Some(std::path::PathBuf::from("/tmp/test")));
             assert!(yes);
@@ -62,3 +65,103 @@ fn test_cli_parsing_version_command() {
         _ => panic!("Expected Version command"),
     }
 }
+
+#[test]
+fn test_cli_parsing_new_command_with_ref() {
+    let args = vec![
+        "claudeforge",
+        "new",
+        "rust",
+        "my-project",
+        "--ref",
+        "v1.2.0",
+    ];
+    let cli = Cli::parse_from(args);
+
+    match cli.command {
+        Commands::New { git_ref, .. } => {
+            assert_eq!(git_ref.as_deref(), Some("v1.2.0"));
+        }
+        _ => panic!("Expected New command"),
+    }
+}
+
+#[test]
+fn test_cli_parsing_new_command_with_vars() {
+    let args = vec![
+        "claudeforge",
+        "new",
+        "rust",
+        "my-project",
+        "--var",
+        "team=payments",
+        "--var",
+        "dsn=postgres://db?sslmode=require",
+        "--vars-file",
+        "vars.yaml",
+    ];
+    let cli = Cli::parse_from(args);
+
+    match cli.command {
+        Commands::New {
+            vars, vars_file, ..
+        } => {
+            assert_eq!(
+                vars,
+                vec![
+                    ("team".to_string(), "payments".to_string()),
+                    (
+                        "dsn".to_string(),
+                        "postgres://db?sslmode=require".to_string()
+                    ),
+                ]
+            );
+            assert_eq!(vars_file, Some(std::path::PathBuf::from("vars.yaml")));
+        }
+        _ => panic!("Expected New command"),
+    }
+
+    assert!(Cli::try_parse_from(["claudeforge", "new", "rust", "p", "--var", "novalue"]).is_err());
+}
+
+#[test]
+fn test_cli_parsing_cache_prune_command() {
+    let args = vec!["claudeforge", "cache", "prune", "--older-than", "2w"];
+    let cli = Cli::parse_from(args);
+
+    match cli.command {
+        Commands::Cache {
+            command: CacheCommands::Prune { older_than },
+        } => {
+            assert_eq!(older_than, chrono::Duration::days(14));
+        }
+        _ => panic!("Expected Cache Prune command"),
+    }
+
+    let args = vec!["claudeforge", "cache", "prune", "--older-than", "3y"];
+    assert!(Cli::try_parse_from(args).is_err());
+}
+
+#[test]
+fn test_cli_parsing_cache_clean_command() {
+    let args = vec!["claudeforge", "cache", "clean", "rust", "--repository"];
+    let cli = Cli::parse_from(args);
+
+    match cli.command {
+        Commands::Cache {
+            command:
+                CacheCommands::Clean {
+                    template,
+                    repository,
+                },
+        } => {
+            assert_eq!(template.as_deref(), Some("rust"));
+            assert!(repository);
+        }
+        _ => panic!("Expected Cache Clean command"),
+    }
+
+    // The flag only applies to one template's repository
+    let args = vec!["claudeforge", "cache", "clean", "--repository"];
+    assert!(Cli::try_parse_from(args).is_err());
+}
@@ -1,4 +1,3 @@
-use claudeforge::cli::Language;
 use claudeforge::error::ClaudeForgeError;
 use claudeforge::template::processor::create_project;
 use tempfile::TempDir;
@@ -18,7 +17,7 @@ async fn test_create_project_directory_exists_with_skip_prompts() {
 
     // Try to create project with skip_prompts = true
     let result = create_project(
-        Language::Rust,
+        "rust",
         project_name.to_string(),
         Some(temp_dir.path().to_path_buf()),
         true, // skip_prompts
@@ -49,7 +48,7 @@ async fn test_create_project_directory_exists_without_skip_prompts() {
 
     // Try to create project with skip_prompts = false
     let result = create_project(
-        Language::Rust,
+        "rust",
         project_name.to_string(),
         Some(temp_dir.path().to_path_buf()),
         false, // skip_prompts
@@ -86,7 +85,7 @@ async fn test_create_project_with_custom_directory() {
 
     // Create project in custom directory
     let result = create_project(
-        Language::Go,
+        "go",
         project_name.to_string(),
         Some(custom_dir.clone()),
         true,
@@ -116,7 +115,7 @@ async fn test_create_project_default_directory() {
 
     // Create project without specifying directory (should use current dir)
     let result = create_project(
-        Language::Python,
+        "python",
         project_name.to_string(),
         None, // Use default directory
         true,
@@ -142,7 +141,7 @@ async fn test_create_project_with_special_characters_in_name() {
     let project_name = "my-special_project.2024";
 
     let result = create_project(
-        Language::Rust,
+        "rust",
         project_name.to_string(),
         Some(temp_dir.path().to_path_buf()),
         true,